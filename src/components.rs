@@ -48,6 +48,7 @@ pub use label::*;
 pub use link::*;
 pub use loading::*;
 pub use menu::*;
+pub use notification::*;
 pub use popup::*;
 pub use progress::*;
 pub use radio::*;
@@ -227,7 +228,35 @@ live_design! {
     pub GIcon = <GIconBase>{}
     pub GToolButton = <GToolButtonBase>{}
     pub GImage = <GImageBase>{}
+    pub GImageViewer = <GImageViewerBase>{}
     pub GInput = <GInputBase>{}
+    pub GAutoComplete = <GAutoCompleteBase>{
+        input: <GInput>{
+            height: Fill,
+            width: Fill,
+        }
+        popup: <GPopup>{
+            height: Fit,
+            width: 180.0,
+            mode: Popup,
+            flow: Down,
+            padding: 4.0,
+            border_width: 1.0,
+            border_radius: 4.0,
+            background_color: #FFFFFF,
+            border_color: #EAECF0,
+            container: <GPopupContainer>{
+                height: 0.0,
+                visible: false,
+            }
+        }
+    }
+    pub GNumberInput = <GNumberInputBase>{
+        input: <GInput>{
+            height: Fill,
+            width: Fill,
+        }
+    }
     pub GShader = <GShaderBase>{}
     pub GDivider = <GDividerBase>{}
     pub GPopupContainer = <GPopupContainerBase>{}
@@ -774,6 +803,8 @@ live_design! {
     pub GSelectItem = <GSelectItemBase>{}
     pub GSelectOptions = <GSelectOptionsBase>{
         height: 144.0,
+        max_height: 240.0,
+        item_height: 36.0,
         width: 180.0,
         padding: {left: 8.0, right: 8.0, top: 6.0, bottom: 6.0},
         flow: Down,
@@ -792,6 +823,62 @@ live_design! {
         flow: Down,
         select_item: <GSelectItem>{},
         select_options: <GSelectOptions>{}
+        select_tag: <GTag>{
+            closeable: true,
+            height: 24.0,
+            padding: {left: 6.0, right: 6.0},
+            font_size: 9.0,
+        }
+        filter_input: <GInput>{
+            height: 32.0,
+            width: Fill,
+            placeholder: "Search",
+            margin: {bottom: 4.0},
+        }
+        loading: <GLoading>{
+            height: 24.0,
+            width: 24.0,
+        }
+        empty_text: "No Data",
+    }
+    pub GToast = <GToastBase>{
+        icon: <GIcon>{
+            height: 18.0,
+            width: 18.0,
+            stroke_width: 1.2,
+        }
+        close: <GIcon>{
+            height: 10.0,
+            width: 10.0,
+            icon_type: Close,
+            cursor: Hand,
+            color: #98A2B3,
+        }
+        action: <GTag>{
+            theme: Info,
+            height: 24.0,
+            font_size: 9.0,
+            cursor: Hand,
+        }
+    }
+    pub GNotification = <GNotificationBase>{
+        toast: <GToast>{}
+    }
+    pub GDialogBox = <GDialogBoxBase>{
+        button: <GButton>{
+            theme: Info,
+            border_width: 1.0,
+            slot: <GLabel>{
+                color: #344054,
+                text: "Cancel"
+            }
+        }
+        primary_button: <GButton>{
+            theme: Primary,
+            slot: <GLabel>{
+                text: "OK"
+            }
+        }
     }
     pub GTabbarItem = <GTabbarItemBase>{
         icon_slot: <GSvg>{
@@ -970,4 +1057,68 @@ live_design! {
             visible: false
         }
     }
+    pub GContextMenuItem = <GMenuItem>{
+        height: 32.0,
+        icon_slot: <GSvg>{
+            visible: false
+        }
+        text_slot: <GLabel>{
+            text: "",
+            font_size: 9.0,
+        }
+        right: <GHLayout>{
+            visible: true,
+            width: Fill,
+            height: Fit,
+            align: {x: 1.0, y: 0.5},
+            shortcut = <GLabel>{
+                text: "",
+                font_size: 8.0,
+                color: #98A2B3,
+            }
+        }
+    }
+    pub GContextSubMenu = <GSubMenu>{
+        title: <GView>{
+            background_visible: false,
+            padding: {
+                left: 12.0,
+                right: 12.0,
+            },
+            height: Fill,
+            width: Fill,
+            align: {
+                x: 0.0,
+                y: 0.5
+            },
+            label = <GLabel>{
+                width: Fill,
+                text: "",
+                font_size: 9.0,
+            }
+            arrow = <GLabel>{
+                text: "›",
+                font_size: 9.0,
+                color: #98A2B3,
+            }
+        }
+    }
+    pub GContextMenu = <GContextMenuBase>{
+        height: Fit,
+        width: Fit,
+        background_visible: false,
+        menu: <GMenu>{
+            height: Fit,
+            width: 200.0,
+            padding: 4.0,
+            border_radius: 6.0,
+            border_width: 1.0,
+            blur_radius: 8.0,
+            spread_radius: 0.0,
+            body: <GVLayout>{
+                height: Fit,
+                width: Fill,
+            }
+        }
+    }
 }
@@ -5,6 +5,10 @@ use makepad_widgets::{
 #[derive(Clone, Debug, DefaultNone)]
 pub enum GDropDownEvent {
     Changed(GDropDownChangedParam),
+    /// the drawer is resized by dragging its edge, `size` is the width (or height) in pixels.
+    /// It is sent once the edge is released and the snap animation is done, not while dragging,
+    /// use `GDropDown::drawer_size` to follow the drag.
+    Resized(GDropDownResizedParam),
     None,
 }
 
@@ -14,6 +18,11 @@ pub struct GDropDownChangedParam {
     pub opened: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct GDropDownResizedParam {
+    pub size: f64,
+}
+
 #[derive(Debug, Clone, Default)]
 pub enum GDropDownToggleKind {
     Click(FingerUpEvent),
@@ -5,18 +5,21 @@ pub use event::*;
 // use event::*;
 use makepad_widgets::*;
 
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
 use crate::{
     getter, ref_area, ref_getter_setter, ref_redraw_mut, setter,
-    shader::manual::{CloseMode, PopupMode, Position, TriggerMode},
+    shader::{
+        draw_view::DrawGView,
+        manual::{in_safe_area, CloseMode, PopupMode, Position, TriggerMode},
+    },
     themes::Themes,
     utils::ToBool,
 };
 use icon_atlas::RefCell;
 
 use super::{
-    popup::{GPopup, GPopupContainer},
+    popup::{GPopup, GPopupContainer, PopupStackGlobal},
     view::GView,
 };
 
@@ -58,13 +61,145 @@ pub struct GDropDown {
     pub close_mode: CloseMode,
     #[rust(true)]
     pub redraw_flag: bool,
+    // hover intent (`trigger_mode: Hover`) -------------------
+    /// seconds the pointer stays on the anchor before the popup opens
+    #[live(0.0)]
+    pub open_delay: f64,
+    /// seconds before the popup closes after the pointer leaves the anchor and the popup
+    #[live(0.0)]
+    pub close_delay: f64,
+    /// keep the popup opened while the pointer moves from the anchor to the popup
+    #[live(true)]
+    pub safe_triangle: bool,
+    /// tooltips in the same group open without `open_delay` once one of them is shown
+    #[live]
+    pub group: LiveId,
+    /// seconds the group stays warm after its last tooltip is closed
+    #[live(0.5)]
+    pub group_timeout: f64,
+    #[rust]
+    open_timer: Timer,
+    #[rust]
+    close_timer: Timer,
+    #[rust]
+    closing: bool,
+    /// the running close timer is the grace of the safe triangle
+    #[rust]
+    grace: bool,
+    /// the last pointer position over the anchor or the popup, the apex of the safe triangle
+    #[rust]
+    last_inside: Option<DVec2>,
+    #[rust]
+    hover_event: Option<FingerHoverEvent>,
+    // drawer (`mode: Drawer`) -------------------
+    /// drag the inner edge of the drawer to resize it
+    #[live(false)]
+    pub resizable: bool,
+    /// min size of the drawer in pixels, `0` means no limit
+    #[live(0.0)]
+    pub min_size: f64,
+    /// max size of the drawer in pixels, `0` means no limit
+    #[live(0.0)]
+    pub max_size: f64,
+    /// close the drawer when the edge is released under `dismiss_threshold` of the min size
+    /// (or of the size when the drag starts if there is no `min_size`), or swiped to close
+    /// faster than `dismiss_velocity`
+    #[live(true)]
+    pub drag_dismiss: bool,
+    #[live(0.5)]
+    pub dismiss_threshold: f64,
+    /// pixels per second of a closing swipe which dismisses the drawer, `0` disables the swipe
+    #[live(800.0)]
+    pub dismiss_velocity: f64,
+    /// width of the draggable zone around the edge
+    #[live(8.0)]
+    pub handle_size: f64,
+    /// seconds of the snap animation after the edge is released
+    #[live(0.2)]
+    pub snap_duration: f64,
+    /// the draggable zone around the edge, it is drawn invisible to take the mouse and touch hits
+    #[live]
+    pub draw_handle: DrawGView,
+    /// size of the drawer set by dragging, `None` uses `proportion`
+    #[rust]
+    drawer_size: Option<f64>,
+    /// size of the drawer in the last draw
+    #[rust]
+    drawn_size: f64,
+    #[rust]
+    drag: Option<DrawerDrag>,
+    #[rust]
+    snap: Option<DrawerSnap>,
+    #[rust]
+    next_frame: NextFrame,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DrawerDrag {
+    start: f64,
+    start_size: f64,
+    /// `drawer_size` before the drag, it is set back after a dismiss
+    restore: Option<f64>,
+    /// position and time of the last move
+    last: f64,
+    last_time: f64,
+    /// growth of the size in pixels per second at the last move, negative while closing
+    velocity: f64,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct DrawerSnap {
+    from: f64,
+    to: f64,
+    start_time: Option<f64>,
+    /// close after the snap, the size is set back to `restore`
+    dismiss: bool,
+    restore: Option<f64>,
+}
+
+/// a swipe is the last move if the edge is released within this time (seconds)
+const SWIPE_WINDOW: f64 = 0.1;
+
+/// the delay before the popup closes while the pointer is in the safe triangle
+const SAFE_TRIANGLE_DELAY: f64 = 0.4;
+
 #[derive(Default, Clone)]
 pub struct PopupMenuGlobal {
     pub map: Rc<RefCell<ComponentMap<LivePtr, GPopup>>>,
 }
 
+/// # Tooltip groups
+/// a group is warm while one of its tooltips is opened and for `group_timeout` after the last one is closed,
+/// tooltips of a warm group open immediately
+#[derive(Default, Clone)]
+pub struct TooltipGroupGlobal {
+    /// count of the opened tooltips and when the last one is closed (seconds since the app start)
+    groups: Rc<RefCell<HashMap<LiveId, (usize, Option<f64>)>>>,
+}
+
+impl TooltipGroupGlobal {
+    pub fn is_warm(cx: &mut Cx, group: LiveId, timeout: f64) -> bool {
+        let global = cx.global::<TooltipGroupGlobal>().clone();
+        let now = cx.seconds_since_app_start();
+        let groups = global.groups.borrow();
+        groups.get(&group).map_or(false, |(opened, closed_at)| {
+            *opened > 0 || closed_at.map_or(false, |time| now - time < timeout)
+        })
+    }
+    fn opened(cx: &mut Cx, group: LiveId) {
+        let global = cx.global::<TooltipGroupGlobal>().clone();
+        global.groups.borrow_mut().entry(group).or_default().0 += 1;
+    }
+    fn closed(cx: &mut Cx, group: LiveId) {
+        let global = cx.global::<TooltipGroupGlobal>().clone();
+        let now = cx.seconds_since_app_start();
+        let mut groups = global.groups.borrow_mut();
+        let state = groups.entry(group).or_default();
+        state.0 = state.0.saturating_sub(1);
+        state.1 = Some(now);
+    }
+}
+
 impl LiveHook for GDropDown {
     fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) {
         self.deref_widget.after_apply(cx, apply, index, nodes);
@@ -95,6 +230,8 @@ impl Widget for GDropDown {
             let global = cx.global::<PopupMenuGlobal>().clone();
             let mut map = global.map.borrow_mut();
             let popup_menu = map.get_mut(&self.popup.unwrap()).unwrap();
+            let uid = self.widget_uid();
+            popup_menu.raise = PopupStackGlobal::take_raise(cx, uid);
             popup_menu.begin(cx);
             match self.mode {
                 PopupMode::Popup | PopupMode::ToolTip => {
@@ -108,76 +245,64 @@ impl Widget for GDropDown {
                         &mut self.redraw_flag,
                     );
                     let container = popup_menu.container_area().rect(cx);
-                    let mut shift = match self.position {
-                        Position::Bottom => DVec2 {
-                            x: -container.size.x / 2.0 + area.size.x / 2.0,
-                            y: area.size.y + self.offset as f64,
-                        },
-                        Position::BottomLeft => DVec2 {
-                            x: 0.0,
-                            y: area.size.y + self.offset as f64,
-                        },
-                        Position::BottomRight => DVec2 {
-                            x: area.size.x - container.size.x,
-                            y: area.size.y + self.offset as f64,
-                        },
-                        Position::Top => DVec2 {
-                            x: 0.0 - area.size.x / 2.0,
-                            y: -self.offset as f64 - container.size.y,
-                        },
-                        Position::TopLeft => DVec2 {
-                            x: 0.0,
-                            y: -self.offset as f64 - container.size.y,
-                        },
-                        Position::TopRight => DVec2 {
-                            x: area.size.x - container.size.x,
-                            y: -self.offset as f64 - container.size.y,
-                        },
-                        Position::Left => DVec2 {
-                            x: -self.offset as f64 - container.size.x,
-                            y: area.size.y / 2.0 - container.size.y / 2.0,
-                        },
-                        Position::LeftTop => DVec2 {
-                            x: -self.offset as f64 - container.size.x,
-                            y: 0.0,
-                        },
-                        Position::LeftBottom => DVec2 {
-                            x: -self.offset as f64 - container.size.x,
-                            y: 0.0 - container.size.y + area.size.y,
-                        },
-                        Position::Right => DVec2 {
-                            x: area.size.x + self.offset as f64,
-                            y: area.size.y / 2.0 - container.size.y / 2.0,
-                        },
-                        Position::RightTop => DVec2 {
-                            x: area.size.x + self.offset as f64,
-                            y: 0.0,
-                        },
-                        Position::RightBottom => DVec2 {
-                            x: area.size.x + self.offset as f64,
-                            y: 0.0 - container.size.y + area.size.y,
-                        },
+                    let mut shift = if let Position::Auto = self.position {
+                        let viewport = Rect {
+                            pos: DVec2::default(),
+                            size: cx.current_pass_size(),
+                        };
+                        // pick the side, flip and slide to stay in the window, the arrow follows the anchor
+                        let (position, shift) =
+                            self.position.place(area, container.size, self.offset as f64, viewport);
+                        let (arrow, angle_offset) = position.arrow(area.size, container.size, shift);
+                        popup_menu.draw_popup.position = arrow;
+                        popup_menu.draw_popup.angle_offset = angle_offset;
+                        shift
+                    } else {
+                        self.position.shift(area.size, container.size, self.offset as f64)
                     };
 
                     shift.x += self.offset_x as f64;
                     shift.y += self.offset_y as f64;
 
                     popup_menu.end(cx, scope, self.area(), shift);
+                    PopupStackGlobal::set_popup_area(cx, uid, popup_menu.area());
                 }
 
                 PopupMode::Dialog => {
                     popup_menu.draw_container(cx, scope, None, 0.0, &mut false);
                     popup_menu.end(cx, scope, Area::Empty, DVec2::default());
+                    PopupStackGlobal::set_popup_area(cx, uid, popup_menu.container_area());
                 }
                 PopupMode::Drawer => {
-                    let _ = popup_menu.draw_container_drawer(
-                        cx,
-                        scope,
-                        self.position,
-                        self.proportion,
-                        &mut self.redraw_flag,
-                    );
+                    if let Some(size) = self.drawer_size {
+                        popup_menu.draw_container_drawer_sized(
+                            cx,
+                            scope,
+                            self.position,
+                            size,
+                            &mut self.redraw_flag,
+                        );
+                    } else {
+                        popup_menu.draw_container_drawer(
+                            cx,
+                            scope,
+                            self.position,
+                            self.proportion,
+                            &mut self.redraw_flag,
+                        );
+                    }
+                    let rect = popup_menu.container_area().rect(cx);
+                    self.drawn_size = if self.position.is_vertical() {
+                        rect.size.y
+                    } else {
+                        rect.size.x
+                    };
+                    if self.resizable {
+                        self.draw_handle.background_visible = 0.0;
+                        self.draw_handle.draw_abs(cx, self.handle_rect(rect));
+                    }
                     popup_menu.end(cx, scope, Area::Empty, DVec2::default());
+                    PopupStackGlobal::set_popup_area(cx, uid, popup_menu.container_area());
                 }
             }
         }
@@ -185,28 +310,67 @@ impl Widget for GDropDown {
         DrawStep::done()
     }
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let sweep_area = self.area();
+        self.handle_event_with(cx, event, scope, sweep_area);
+    }
+    /// `sweep_area` is the area locked by the popup which contains this dropdown (if any)
+    fn handle_event_with(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        scope: &mut Scope,
+        sweep_area: Area,
+    ) {
+        if self.open_timer.is_event(event).is_some() {
+            let e_kind = self
+                .hover_event
+                .take()
+                .map_or(GDropDownToggleKind::Other, GDropDownToggleKind::Hover);
+            self.open_inner(cx, e_kind);
+        }
+        if self.close_timer.is_event(event).is_some() {
+            self.closing = false;
+            self.close_by_hover(cx);
+        }
+        let accepts = PopupStackGlobal::accepts(cx, self.widget_uid(), event);
+        if let (Event::MouseMove(e), true) =
+            (event, accepts && self.opened && self.trigger_mode.is_hover())
+        {
+            self.hover_intent(cx, e.abs);
+        }
+        if let Some(ne) = self.next_frame.is_event(event) {
+            self.snap_tick(cx, ne.time);
+        }
         if self.opened && self.popup.is_some() {
             let global = cx.global::<PopupMenuGlobal>().clone();
             let mut map = global.map.borrow_mut();
             let popup_menu = map.get_mut(&self.popup.unwrap()).unwrap();
+            if accepts
+                && self.mode == PopupMode::Drawer
+                && self.resizable
+                && self.handle_drawer_drag(cx, event)
+            {
+                return;
+            }
             popup_menu.handle_event_with(cx, event, scope, self.area());
             if let Event::MouseDown(e) = event {
-                match self.mode {
-                    PopupMode::Popup | PopupMode::ToolTip => {
-                        let is_in = popup_menu.menu_contains_pos(cx, e.abs);
-                        self.close_inner(cx, GDropDownToggleKind::Other, is_in);
-                    }
-
+                let uid = self.widget_uid();
+                // a click in an upper popup is in this one, only the top popup is closed by a click outside
+                let is_in = match self.mode {
+                    PopupMode::Popup | PopupMode::ToolTip => popup_menu.menu_contains_pos(cx, e.abs),
                     PopupMode::Dialog | PopupMode::Drawer => {
-                        let is_in = popup_menu.container_contains_pos(cx, e.abs);
-                        self.close_inner(cx, GDropDownToggleKind::Other, is_in);
+                        popup_menu.container_contains_pos(cx, e.abs)
                     }
-                }
+                } || PopupStackGlobal::is_over_above(cx, uid, e.abs);
+                let is_in = is_in
+                    || (self.close_mode == CloseMode::Out
+                        && !PopupStackGlobal::close_out(cx, uid, e.time));
+                self.close_inner(cx, GDropDownToggleKind::Other, is_in);
                 return;
             }
         }
 
-        match event.hits_with_sweep_area(cx, self.area(), self.area()) {
+        match event.hits_with_sweep_area(cx, self.area(), sweep_area) {
             // template remove -------------------------------------------------------------------
             // Hit::KeyFocus(_) => {
             //     // self.animator_play(cx, id!(focus.on));
@@ -226,13 +390,28 @@ impl Widget for GDropDown {
             Hit::FingerHoverIn(e) => {
                 cx.set_cursor(MouseCursor::Hand);
                 if self.trigger_mode.is_hover() {
-                    self.open_inner(cx, GDropDownToggleKind::Hover(e));
+                    self.stop_close(cx);
+                    let warm = TooltipGroupGlobal::is_warm(cx, self.group, self.group_timeout);
+                    if self.opened {
+                        // back from the popup, it stays opened
+                    } else if self.open_delay > 0.0 && !warm {
+                        cx.stop_timer(self.open_timer);
+                        self.open_timer = cx.start_timeout(self.open_delay);
+                        self.hover_event = Some(e);
+                    } else {
+                        self.open_inner(cx, GDropDownToggleKind::Hover(e));
+                    }
                 }
             }
             Hit::FingerHoverOut(f) => {
                 cx.set_cursor(MouseCursor::Default);
                 if self.trigger_mode.is_hover() {
-                    self.close_inner(cx, GDropDownToggleKind::Hover(f), false);
+                    // the pending open is canceled, the close is decided by `hover_intent`
+                    cx.stop_timer(self.open_timer);
+                    self.hover_event = Some(f.clone());
+                    if self.opened {
+                        self.hover_intent(cx, f.abs);
+                    }
                 }
             }
             Hit::FingerUp(e) => {
@@ -279,7 +458,7 @@ impl GDropDown {
         // we don't need to care close mode here
         self.opened = false;
         self.redraw(cx);
-        cx.sweep_unlock(self.area());
+        self.closed_inner(cx);
         self.active_toggled(cx, GDropDownToggleKind::Other);
         self.redraw_flag = true;
     }
@@ -297,7 +476,10 @@ impl GDropDown {
         }
         self.opened = true;
         self.redraw(cx);
-        cx.sweep_lock(self.area());
+        PopupStackGlobal::open(cx, self.widget_uid(), self.area());
+        if self.group != LiveId(0) {
+            TooltipGroupGlobal::opened(cx, self.group);
+        }
         self.active_toggled(cx, e_kind);
     }
     /// close the popup only inner control
@@ -320,11 +502,254 @@ impl GDropDown {
         if flag {
             self.opened = false;
             self.redraw(cx);
-            cx.sweep_unlock(self.area());
+            self.closed_inner(cx);
             self.active_toggled(cx, e_kind);
         }
         self.redraw_flag = true;
     }
+    /// clean up after the popup is closed
+    fn closed_inner(&mut self, cx: &mut Cx) {
+        PopupStackGlobal::close(cx, self.widget_uid());
+        self.stop_close(cx);
+        cx.stop_timer(self.open_timer);
+        self.last_inside = None;
+        if self.group != LiveId(0) {
+            TooltipGroupGlobal::closed(cx, self.group);
+        }
+    }
+    /// the draggable zone around the inner edge of the drawer `rect`
+    fn handle_rect(&self, rect: Rect) -> Rect {
+        let size = self.handle_size;
+        match self.position.to_drawer() {
+            Position::Left => Rect {
+                pos: dvec2(rect.pos.x + rect.size.x - size, rect.pos.y),
+                size: dvec2(size * 2.0, rect.size.y),
+            },
+            Position::Right => Rect {
+                pos: dvec2(rect.pos.x - size, rect.pos.y),
+                size: dvec2(size * 2.0, rect.size.y),
+            },
+            Position::Top => Rect {
+                pos: dvec2(rect.pos.x, rect.pos.y + rect.size.y - size),
+                size: dvec2(rect.size.x, size * 2.0),
+            },
+            _ => Rect {
+                pos: dvec2(rect.pos.x, rect.pos.y - size),
+                size: dvec2(rect.size.x, size * 2.0),
+            },
+        }
+    }
+    /// ## drag the inner edge of the drawer (mouse or touch)
+    /// back `true` if the event is taken by the drag
+    fn handle_drawer_drag(&mut self, cx: &mut Cx, event: &Event) -> bool {
+        let side = self.position.to_drawer();
+        let vertical = side.is_vertical();
+        // the main axis of the drawer, the size grows with it for `Left`/`Top`
+        let main = |pos: DVec2| if vertical { pos.y } else { pos.x };
+        let dir = match side {
+            Position::Left | Position::Top => 1.0,
+            _ => -1.0,
+        };
+        let cursor = if vertical {
+            MouseCursor::NsResize
+        } else {
+            MouseCursor::EwResize
+        };
+        match event.hits_with_sweep_area(cx, self.draw_handle.area(), self.area()) {
+            Hit::FingerHoverIn(_) | Hit::FingerHoverOver(_) => {
+                cx.set_cursor(cursor);
+                false
+            }
+            Hit::FingerDown(e) => {
+                // a drag during a dismiss snap keeps the size from before the dismissing drag
+                let restore = match self.snap.take() {
+                    Some(snap) if snap.dismiss => snap.restore,
+                    _ => self.drawer_size,
+                };
+                self.drag = Some(DrawerDrag {
+                    start: main(e.abs),
+                    start_size: self.drawn_size,
+                    restore,
+                    last: main(e.abs),
+                    last_time: e.time,
+                    velocity: 0.0,
+                });
+                cx.set_cursor(cursor);
+                true
+            }
+            Hit::FingerMove(e) => {
+                let Some(drag) = self.drag.as_mut() else {
+                    return false;
+                };
+                let pos = main(e.abs);
+                if e.time > drag.last_time {
+                    drag.velocity = (pos - drag.last) * dir / (e.time - drag.last_time);
+                }
+                drag.last = pos;
+                drag.last_time = e.time;
+                let drag = *drag;
+                let mut size = (drag.start_size + (main(e.abs) - drag.start) * dir).max(0.0);
+                if self.max_size > 0.0 {
+                    size = size.min(self.max_size);
+                }
+                self.set_drawer_size(cx, size);
+                cx.set_cursor(cursor);
+                true
+            }
+            Hit::FingerUp(e) => {
+                let Some(drag) = self.drag.take() else {
+                    return false;
+                };
+                let size = self.drawer_size.unwrap_or(drag.start_size);
+                let min = if self.min_size > 0.0 {
+                    self.min_size
+                } else {
+                    drag.start_size
+                };
+                let swiped = self.dismiss_velocity > 0.0
+                    && e.time - drag.last_time <= SWIPE_WINDOW
+                    && -drag.velocity > self.dismiss_velocity;
+                let dismiss = self.drag_dismiss && (swiped || size < min * self.dismiss_threshold);
+                let to = if dismiss {
+                    0.0
+                } else {
+                    self.clamp_size(size)
+                };
+                self.snap = Some(DrawerSnap {
+                    from: size,
+                    to,
+                    start_time: None,
+                    dismiss,
+                    restore: drag.restore,
+                });
+                self.next_frame = cx.new_next_frame();
+                true
+            }
+            _ => false,
+        }
+    }
+    fn clamp_size(&self, size: f64) -> f64 {
+        let size = size.max(self.min_size);
+        if self.max_size > 0.0 {
+            size.min(self.max_size)
+        } else {
+            size
+        }
+    }
+    fn snap_tick(&mut self, cx: &mut Cx, time: f64) {
+        let Some(mut snap) = self.snap else {
+            return;
+        };
+        let start_time = *snap.start_time.get_or_insert(time);
+        let t = if self.snap_duration > 0.0 {
+            ((time - start_time) / self.snap_duration).min(1.0)
+        } else {
+            1.0
+        };
+        // ease out cubic
+        let eased = 1.0 - (1.0 - t).powi(3);
+        self.set_drawer_size(cx, snap.from + (snap.to - snap.from) * eased);
+        if t < 1.0 {
+            self.snap = Some(snap);
+            self.next_frame = cx.new_next_frame();
+            return;
+        }
+        self.snap = None;
+        if snap.dismiss {
+            self.close(cx);
+            // a drawer sized by `proportion` keeps following it
+            self.drawer_size = snap.restore;
+        } else if self.event_key {
+            cx.widget_action(
+                self.widget_uid(),
+                self.scope_path.as_ref().unwrap(),
+                GDropDownEvent::Resized(GDropDownResizedParam { size: snap.to }),
+            );
+        }
+    }
+    /// the size of the drawer in pixels (width for `Left`/`Right`, height for `Top`/`Bottom`)
+    pub fn drawer_size(&self) -> f64 {
+        self.drawer_size.unwrap_or(self.drawn_size)
+    }
+    /// set the size of the drawer in pixels, it is used instead of `proportion`
+    pub fn set_drawer_size(&mut self, cx: &mut Cx, size: f64) {
+        self.drawer_size = Some(size);
+        self.redraw_flag = true;
+        self.redraw(cx);
+    }
+    pub fn resized(&self, actions: &Actions) -> Option<GDropDownResizedParam> {
+        if let GDropDownEvent::Resized(e) = actions.find_widget_action(self.widget_uid()).cast() {
+            Some(e)
+        } else {
+            None
+        }
+    }
+    fn popup_rect(&self, cx: &mut Cx) -> Rect {
+        let global = cx.global::<PopupMenuGlobal>().clone();
+        let map = global.map.borrow();
+        match self.popup.and_then(|popup| map.get(&popup)) {
+            Some(popup_menu) => match self.mode {
+                PopupMode::Popup | PopupMode::ToolTip => popup_menu.area().clipped_rect(cx),
+                PopupMode::Dialog | PopupMode::Drawer => {
+                    popup_menu.container_area().clipped_rect(cx)
+                }
+            },
+            None => Rect::default(),
+        }
+    }
+    /// ## decide if the hover popup should be closed when the pointer moves to `pos`
+    /// - over the anchor or the popup (or an upper popup): stay opened
+    /// - in the safe triangle between the last inside position and the popup: close after a grace delay
+    /// - otherwise: close after `close_delay`
+    fn hover_intent(&mut self, cx: &mut Cx, pos: DVec2) {
+        let popup_rect = self.popup_rect(cx);
+        let over = self.area().clipped_rect(cx).contains(pos)
+            || popup_rect.contains(pos)
+            || PopupStackGlobal::is_over_above(cx, self.widget_uid(), pos);
+        if over {
+            self.last_inside = Some(pos);
+            self.stop_close(cx);
+            return;
+        }
+        let in_triangle = self.safe_triangle
+            && self
+                .last_inside
+                .map_or(false, |apex| in_safe_area(apex, popup_rect, pos));
+        if in_triangle {
+            if !self.closing {
+                self.start_close(cx, self.close_delay.max(SAFE_TRIANGLE_DELAY));
+                self.grace = true;
+            }
+        } else {
+            // out of the triangle, the grace is over
+            self.last_inside = None;
+            if !self.closing || self.grace {
+                self.start_close(cx, self.close_delay);
+            }
+        }
+    }
+    fn start_close(&mut self, cx: &mut Cx, delay: f64) {
+        cx.stop_timer(self.close_timer);
+        self.grace = false;
+        if delay > 0.0 {
+            self.close_timer = cx.start_timeout(delay);
+            self.closing = true;
+        } else {
+            self.closing = false;
+            self.close_by_hover(cx);
+        }
+    }
+    fn stop_close(&mut self, cx: &mut Cx) {
+        cx.stop_timer(self.close_timer);
+        self.closing = false;
+    }
+    fn close_by_hover(&mut self, cx: &mut Cx) {
+        let e_kind = self
+            .hover_event
+            .take()
+            .map_or(GDropDownToggleKind::Other, GDropDownToggleKind::Hover);
+        self.close_inner(cx, e_kind, false);
+    }
     fn active_toggled(&mut self, cx: &mut Cx, e_kind: GDropDownToggleKind) {
         cx.widget_action(
             self.widget_uid(),
@@ -555,4 +980,16 @@ impl GDropDownRef {
             None
         }
     }
+    pub fn resized(&self, actions: &Actions) -> Option<GDropDownResizedParam> {
+        self.borrow().and_then(|c_ref| c_ref.resized(actions))
+    }
+    /// the size of the drawer in pixels, persist it and set it back with `set_drawer_size`
+    pub fn drawer_size(&self) -> f64 {
+        self.borrow().map_or(0.0, |c_ref| c_ref.drawer_size())
+    }
+    pub fn set_drawer_size(&self, cx: &mut Cx, size: f64) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_drawer_size(cx, size);
+        }
+    }
 }
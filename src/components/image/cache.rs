@@ -0,0 +1,349 @@
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use makepad_widgets::*;
+
+use super::decode::{ImageFrame, PreparedImage};
+use crate::utils::image_cache_path;
+
+/// default memory budget of the decoded images: 64MB
+pub const IMAGE_CACHE_BUDGET: usize = 64 * 1024 * 1024;
+
+/// hash bytes or a string to a short key, used for base64 srcs and the file names of the disk cache
+pub fn hash_key<T: Hash + ?Sized>(value: &T) -> String {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// write the downloaded bytes to the disk cache, the bytes go to a temp file which is renamed,
+/// so a reader never gets a partly written entry
+pub fn write_disk_cache(path: &Path, buf: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("part");
+    std::fs::write(&tmp, buf)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// A least recently used map bounded by the sum of the entry sizes
+#[derive(Debug, Clone)]
+pub struct ImageLru<T> {
+    entries: HashMap<String, (T, usize, u64)>,
+    tick: u64,
+    used: usize,
+    budget: usize,
+}
+
+impl<T> Default for ImageLru<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            tick: 0,
+            used: 0,
+            budget: IMAGE_CACHE_BUDGET,
+        }
+    }
+}
+
+impl<T> ImageLru<T> {
+    pub fn get(&mut self, key: &str) -> Option<&T> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(value, _, last)| {
+            *last = tick;
+            &*value
+        })
+    }
+    /// insert the value and drop the least recently used ones until the size is in the budget,
+    /// a value bigger than the whole budget is not cached
+    pub fn insert(&mut self, key: &str, value: T, size: usize) {
+        self.remove(key);
+        if size > self.budget {
+            return;
+        }
+        self.tick += 1;
+        self.used += size;
+        self.entries.insert(key.to_string(), (value, size, self.tick));
+        self.evict();
+    }
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        self.entries.remove(key).map(|(value, size, _)| {
+            self.used -= size;
+            value
+        })
+    }
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict();
+    }
+    pub fn used(&self) -> usize {
+        self.used
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used = 0;
+    }
+    fn evict(&mut self) {
+        while self.used > self.budget {
+            let Some(key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, _, last))| *last)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&key);
+        }
+    }
+}
+
+/// the state of a url download
+#[derive(Debug, Clone)]
+pub enum DownloadState {
+    Pending,
+    Loaded(Arc<PreparedImage>),
+    Failed(String),
+}
+
+#[derive(Debug)]
+struct Download {
+    state: DownloadState,
+    /// count of the images waiting for the download
+    waiters: usize,
+}
+
+/// # Downloads
+/// The url downloads shared by the images, it is updated by the download thread, so the images waiting
+/// for a url get the result even if the image which has started the download is dropped.
+/// A finished download is kept until its last waiter has taken it, a failed url is downloaded again
+/// only by a new load after that.
+#[derive(Debug, Default, Clone)]
+pub struct Downloads {
+    map: Arc<Mutex<HashMap<String, Download>>>,
+}
+
+impl Downloads {
+    /// wait for the url, back `true` if there is no download of the url and the caller should start it
+    pub fn join(&self, url: &str) -> bool {
+        let mut map = self.map.lock().unwrap();
+        if let Some(download) = map.get_mut(url) {
+            download.waiters += 1;
+            false
+        } else {
+            map.insert(
+                url.to_string(),
+                Download {
+                    state: DownloadState::Pending,
+                    waiters: 1,
+                },
+            );
+            true
+        }
+    }
+    /// called by the download thread, the waiters are signaled, the result is dropped if no one is waiting
+    pub fn finish(&self, url: &str, result: Result<PreparedImage, String>) {
+        let mut map = self.map.lock().unwrap();
+        if let Some(download) = map.get_mut(url) {
+            if download.waiters == 0 {
+                map.remove(url);
+            } else {
+                download.state = match result {
+                    Ok(image) => DownloadState::Loaded(Arc::new(image)),
+                    Err(e) => DownloadState::Failed(e),
+                };
+            }
+        }
+        SignalToUI::set_ui_signal();
+    }
+    pub fn state(&self, url: &str) -> Option<DownloadState> {
+        let map = self.map.lock().unwrap();
+        map.get(url).map(|download| download.state.clone())
+    }
+    /// stop waiting for the url (the result is taken, another src is loaded or the image is dropped)
+    pub fn leave(&self, url: &str) {
+        let mut map = self.map.lock().unwrap();
+        if let Some(download) = map.get_mut(url) {
+            download.waiters = download.waiters.saturating_sub(1);
+            if download.waiters == 0 && !matches!(download.state, DownloadState::Pending) {
+                map.remove(url);
+            }
+        }
+    }
+}
+
+/// a cached image, the frames of an animated image are shared as a whole
+#[derive(Debug, Clone)]
+pub enum CachedImage {
+    Still(Texture),
+    Animated(Rc<Vec<ImageFrame>>),
+}
+
+impl CachedImage {
+    /// the size in bytes, counted as rgba8
+    pub fn size(&self, cx: &mut Cx) -> usize {
+        let size = |texture: &Texture| {
+            texture
+                .get_format(cx)
+                .vec_width_height()
+                .map_or(0, |(width, height)| width * height * 4)
+        };
+        match self {
+            CachedImage::Still(texture) => size(texture),
+            CachedImage::Animated(frames) => frames.iter().map(|frame| size(&frame.texture)).sum(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ImageCache {
+    pub textures: ImageLru<CachedImage>,
+    /// urls which are downloading, other images with the same url share the download
+    pub downloads: Downloads,
+    /// store the downloaded bytes in the disk (`image_cache_path()`)
+    pub disk_cache: bool,
+}
+
+/// # Image Cache
+/// The decoded textures are shared by all `GImage` with the same src (path, url or hash of the base64 data),
+/// the least recently used textures are dropped when the memory budget (`IMAGE_CACHE_BUDGET` by default) is exceeded.
+/// All frames of an animated image are counted in the budget.
+/// ## Example
+/// ```rust
+/// ImageCacheGlobal::set_budget(cx, 128 * 1024 * 1024);
+/// ImageCacheGlobal::set_disk_cache(cx, true);
+/// ```
+#[derive(Default, Clone)]
+pub struct ImageCacheGlobal {
+    pub cache: Rc<RefCell<ImageCache>>,
+}
+
+impl ImageCacheGlobal {
+    pub fn get(cx: &mut Cx, key: &str) -> Option<CachedImage> {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        let mut cache = global.cache.borrow_mut();
+        cache.textures.get(key).cloned()
+    }
+    /// cache the image, the size is counted as rgba8
+    pub fn insert(cx: &mut Cx, key: &str, image: CachedImage) {
+        let size = image.size(cx);
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        let mut cache = global.cache.borrow_mut();
+        cache.textures.insert(key, image, size);
+    }
+    pub fn remove(cx: &mut Cx, key: &str) {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        let mut cache = global.cache.borrow_mut();
+        let _ = cache.textures.remove(key);
+    }
+    /// set the memory budget in bytes
+    pub fn set_budget(cx: &mut Cx, budget: usize) {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        global.cache.borrow_mut().textures.set_budget(budget);
+    }
+    /// enable the disk cache, it stays disabled if the cache directory can not be created
+    pub fn set_disk_cache(cx: &mut Cx, disk_cache: bool) {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        global.cache.borrow_mut().disk_cache = disk_cache && image_cache_path().is_some();
+    }
+    /// the file of the url in the disk cache if the disk cache is enabled
+    pub fn disk_path(cx: &mut Cx, url: &str) -> Option<PathBuf> {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        let disk_cache = global.cache.borrow().disk_cache;
+        disk_cache
+            .then(image_cache_path)
+            .flatten()
+            .map(|path| path.join(hash_key(url)))
+    }
+    /// remove the entry of the url from the disk cache, used when its bytes can not be decoded
+    pub fn remove_disk(cx: &mut Cx, url: &str) {
+        if let Some(path) = Self::disk_path(cx, url) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    /// the downloads shared by the images with `cache`
+    pub fn downloads(cx: &mut Cx) -> Downloads {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        let cache = global.cache.borrow();
+        cache.downloads.clone()
+    }
+    /// drop all cached textures, the disk cache is kept
+    pub fn clear(cx: &mut Cx) {
+        let global = cx.global::<ImageCacheGlobal>().clone();
+        global.cache.borrow_mut().textures.clear();
+    }
+}
+
+#[cfg(test)]
+mod test_image_cache {
+    use super::{write_disk_cache, DownloadState, Downloads, ImageLru, PreparedImage};
+
+    #[test]
+    fn evict_least_recently_used() {
+        let mut lru = ImageLru::default();
+        lru.set_budget(100);
+        lru.insert("a", 1, 40);
+        lru.insert("b", 2, 40);
+        // `a` is used, so `b` is the least recently used one
+        assert_eq!(lru.get("a"), Some(&1));
+        lru.insert("c", 3, 40);
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("a"), Some(&1));
+        assert_eq!(lru.used(), 80);
+        // bigger than the budget, not cached
+        lru.insert("d", 4, 120);
+        assert_eq!(lru.len(), 2);
+        lru.set_budget(50);
+        assert_eq!(lru.len(), 1);
+        assert_eq!(lru.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn downloads_failed_once() {
+        let downloads = Downloads::default();
+        assert!(downloads.join("a"));
+        // the second image waits for the first download
+        assert!(!downloads.join("a"));
+        downloads.finish("a", Err("404".to_string()));
+        assert!(matches!(downloads.state("a"), Some(DownloadState::Failed(_))));
+        downloads.leave("a");
+        assert!(matches!(downloads.state("a"), Some(DownloadState::Failed(_))));
+        // the result is dropped with the last waiter, a new load downloads it again
+        downloads.leave("a");
+        assert!(downloads.state("a").is_none());
+        assert!(downloads.join("a"));
+    }
+
+    #[test]
+    fn downloads_without_waiter() {
+        let downloads = Downloads::default();
+        assert!(downloads.join("a"));
+        // the image is dropped before the download is done
+        downloads.leave("a");
+        assert!(matches!(downloads.state("a"), Some(DownloadState::Pending)));
+        downloads.finish("a", Ok(PreparedImage::Bytes(vec![1, 2])));
+        assert!(downloads.state("a").is_none());
+    }
+
+    #[test]
+    fn disk_cache_rename() {
+        let dir = std::env::temp_dir().join("gen_image_cache_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("entry");
+        write_disk_cache(&path, &[1, 2, 3]).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), vec![1, 2, 3]);
+        // the temp file is renamed, not left next to the entry
+        assert!(!path.with_extension("part").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::Cursor;
+
+use ::image::{
+    codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
+    AnimationDecoder, Frame, ImageFormat, RgbaImage,
+};
+use makepad_widgets::*;
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{Options, Tree},
+};
+
+/// delay of a frame without delay, the same as the browsers
+const DEFAULT_FRAME_DELAY: f64 = 0.1;
+
+/// A decoded frame, `data` is packed as makepad `VecBGRAu8_32` (`0xAARRGGBB`)
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u32>,
+    /// seconds to show the frame, `0.0` for a still image
+    pub delay: f64,
+}
+
+impl DecodedFrame {
+    pub fn from_rgba(image: RgbaImage, delay: f64) -> Self {
+        let (width, height) = image.dimensions();
+        let data = image
+            .pixels()
+            .map(|p| {
+                let [r, g, b, a] = p.0;
+                (a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
+            })
+            .collect();
+        Self {
+            width: width as usize,
+            height: height as usize,
+            data,
+            delay,
+        }
+    }
+    fn from_frame(frame: Frame) -> Self {
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let delay = if numer == 0 || denom == 0 {
+            DEFAULT_FRAME_DELAY
+        } else {
+            numer as f64 / denom as f64 / 1000.0
+        };
+        Self::from_rgba(frame.into_buffer(), delay)
+    }
+    pub fn to_texture(&self, cx: &mut Cx) -> Texture {
+        Texture::new_with_format(
+            cx,
+            TextureFormat::VecBGRAu8_32 {
+                width: self.width,
+                height: self.height,
+                data: Some(self.data.clone()),
+                updated: TextureUpdated::Full,
+            },
+        )
+    }
+}
+
+/// ## A downloaded image prepared in the download thread
+/// PNG and JPEG are decoded by makepad in the ui thread, the other types (and SVG) are decoded to frames
+/// in the download thread, so the ui thread only creates the textures.
+#[derive(Debug)]
+pub enum PreparedImage {
+    Bytes(Vec<u8>),
+    Frames(Vec<DecodedFrame>),
+}
+
+impl PreparedImage {
+    pub fn prepare(buf: Vec<u8>) -> Result<Self, String> {
+        match imghdr::from_bytes(&buf) {
+            Some(imghdr::Type::Png) if !is_apng(&buf) => Ok(Self::Bytes(buf)),
+            Some(imghdr::Type::Jpeg) => Ok(Self::Bytes(buf)),
+            None if is_svg(&buf) => decode_svg(&buf)
+                .map(Self::Frames)
+                .map_err(|e| e.to_string()),
+            None => Err("unsupported image format".to_string()),
+            Some(ty) => decode_frames(&buf, ty)
+                .map(Self::Frames)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// A frame of an animated image
+#[derive(Debug, Clone)]
+pub struct ImageFrame {
+    pub texture: Texture,
+    pub delay: f64,
+}
+
+/// decode the types which makepad can not decode (gif, webp, bmp and animated png),
+/// back more than one frame if the image is animated
+pub fn decode_frames(
+    buf: &[u8],
+    ty: imghdr::Type,
+) -> Result<Vec<DecodedFrame>, Box<dyn std::error::Error>> {
+    let frames = match ty {
+        imghdr::Type::Gif => GifDecoder::new(Cursor::new(buf))?
+            .into_frames()
+            .collect_frames()?,
+        imghdr::Type::Webp => {
+            let decoder = WebPDecoder::new(Cursor::new(buf))?;
+            if !decoder.has_animation() {
+                return decode_still(buf, ImageFormat::WebP);
+            }
+            decoder.into_frames().collect_frames()?
+        }
+        imghdr::Type::Png => {
+            let decoder = PngDecoder::new(Cursor::new(buf))?;
+            if !decoder.is_apng()? {
+                return decode_still(buf, ImageFormat::Png);
+            }
+            decoder.apng()?.into_frames().collect_frames()?
+        }
+        imghdr::Type::Bmp => return decode_still(buf, ImageFormat::Bmp),
+        _ => return Err(image_cache::ImageError::UnsupportedFormat.into()),
+    };
+    if frames.is_empty() {
+        return Err("the image has no frame".into());
+    }
+    Ok(frames.into_iter().map(DecodedFrame::from_frame).collect())
+}
+
+/// an animated png has the `acTL` chunk before the first `IDAT` chunk
+pub fn is_apng(buf: &[u8]) -> bool {
+    // 8 bytes signature, then each chunk is length (4), type (4), data and crc (4)
+    let mut pos = 8;
+    while let Some(header) = buf.get(pos..pos + 8) {
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        match &header[4..8] {
+            b"acTL" => return true,
+            b"IDAT" => return false,
+            _ => pos += 12 + len,
+        }
+    }
+    false
+}
+
+/// an svg has no magic bytes, it is found by its root element
+pub fn is_svg(buf: &[u8]) -> bool {
+    let head = &buf[..buf.len().min(1024)];
+    let start = head.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(head.len());
+    let head = &head[start..];
+    head.starts_with(b"<svg")
+        || (head.starts_with(b"<?xml") && head.windows(4).any(|w| w == b"<svg"))
+}
+
+/// rasterize an svg at its own size (`width` / `height` or the `viewBox`) to a still frame
+pub fn decode_svg(buf: &[u8]) -> Result<Vec<DecodedFrame>, Box<dyn std::error::Error>> {
+    let tree = Tree::from_data(buf, &Options::default())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        Pixmap::new(size.width(), size.height()).ok_or("the size of the svg is empty")?;
+    resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+    // the pixmap is premultiplied, the texture is not
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let p = p.demultiply();
+            [p.red(), p.green(), p.blue(), p.alpha()]
+        })
+        .collect();
+    let image = RgbaImage::from_raw(size.width(), size.height(), data)
+        .ok_or("the size of the svg is invalid")?;
+    Ok(vec![DecodedFrame::from_rgba(image, 0.0)])
+}
+
+fn decode_still(
+    buf: &[u8],
+    format: ImageFormat,
+) -> Result<Vec<DecodedFrame>, Box<dyn std::error::Error>> {
+    let image = ::image::load_from_memory_with_format(buf, format)?.to_rgba8();
+    Ok(vec![DecodedFrame::from_rgba(image, 0.0)])
+}
+
+#[cfg(test)]
+mod test_decode {
+    use ::image::{Rgba, RgbaImage};
+
+    use super::{decode_svg, is_apng, is_svg, DecodedFrame};
+
+    fn chunk(ty: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(ty);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&[0; 4]);
+        chunk
+    }
+
+    fn png(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut buf = b"\x89PNG\r\n\x1a\n".to_vec();
+        for chunk in chunks {
+            buf.extend_from_slice(chunk);
+        }
+        buf
+    }
+
+    #[test]
+    fn pack_bgra() {
+        let image = RgbaImage::from_pixel(2, 1, Rgba([0x11, 0x22, 0x33, 0x44]));
+        let frame = DecodedFrame::from_rgba(image, 0.0);
+        assert_eq!((frame.width, frame.height), (2, 1));
+        assert_eq!(frame.data, vec![0x44112233, 0x44112233]);
+    }
+
+    #[test]
+    fn apng_chunks() {
+        let ihdr = chunk(b"IHDR", &[0; 13]);
+        let animated = png(&[ihdr.clone(), chunk(b"acTL", &[0; 8]), chunk(b"IDAT", &[1, 2])]);
+        assert!(is_apng(&animated));
+        // `acTL` in the image data is not a chunk
+        let still = png(&[ihdr.clone(), chunk(b"IDAT", b"acTL"), chunk(b"IEND", &[])]);
+        assert!(!is_apng(&still));
+        // truncated
+        assert!(!is_apng(&animated[..20]));
+    }
+
+    #[test]
+    fn svg() {
+        assert!(is_svg(b"  <svg xmlns=\"http://www.w3.org/2000/svg\"></svg>"));
+        assert!(is_svg(b"<?xml version=\"1.0\"?>\n<svg></svg>"));
+        assert!(!is_svg(b"<html></html>"));
+    }
+
+    #[test]
+    fn svg_raster() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="2" height="1"><rect width="2" height="1" fill="#ff0000"/></svg>"##;
+        let frames = decode_svg(svg).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!((frames[0].width, frames[0].height), (2, 1));
+        assert_eq!(frames[0].data, vec![0xFFFF0000, 0xFFFF0000]);
+        assert!(decode_svg(b"<svg").is_err());
+    }
+}
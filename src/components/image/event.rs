@@ -1,10 +1,12 @@
-use makepad_widgets::{ActionDefaultRef, DefaultNone, FingerHoverEvent, FingerUpEvent};
+use makepad_widgets::{ActionDefaultRef, DVec2, DefaultNone, FingerHoverEvent, FingerUpEvent};
 
 #[derive(Clone, Debug, DefaultNone)]
 pub enum GImageEvent {
     HoverIn(GImageHoverParam),
     HoverOut(GImageHoverParam),
     Clicked(GImageClickedParam),
+    Loaded(GImageLoadedParam),
+    Failed(GImageFailedParam),
     None,
 }
 
@@ -17,3 +19,32 @@ pub struct GImageHoverParam {
 pub struct GImageClickedParam {
     pub e: FingerUpEvent,
 }
+
+#[derive(Clone, Debug)]
+pub struct GImageLoadedParam {
+    /// the src which is loaded
+    pub src: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct GImageFailedParam {
+    /// the src which can not be loaded
+    pub src: String,
+    /// the reason, such as the network error or the unsupported format
+    pub error: String,
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GImageViewerEvent {
+    /// the zoom (or the pan by zooming) is changed
+    Zoomed(GImageViewerZoomedParam),
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct GImageViewerZoomedParam {
+    /// zoom level, `1.0` is fit to the view
+    pub zoom: f64,
+    /// the texture position (0.0 - 1.0) at the top left of the view
+    pub pan: DVec2,
+}
@@ -1,30 +1,51 @@
+pub mod cache;
+pub mod decode;
 mod event;
 pub mod register;
+pub mod viewer;
 
-use std::{
-    fs::File,
-    io::Read,
-    path::{Path, PathBuf},
-    str::FromStr,
-};
+use std::{path::PathBuf, rc::Rc, str::FromStr};
 
 use base64::{engine::general_purpose, Engine};
+pub use cache::*;
+use decode::{decode_frames, decode_svg, is_apng, is_svg, DecodedFrame, ImageFrame, PreparedImage};
 pub use event::*;
+pub use viewer::*;
 
 use image_cache::{ImageCacheImpl, ImageFit};
 use makepad_widgets::{image_cache::ImageError, *};
 
+use super::loading::GLoading;
 use crate::{
-    active_event, event_option, getter, pure_after_apply, ref_area, ref_event_option, ref_getter_setter, ref_redraw, ref_render, render_after_apply, set_event, set_scope_path, setter, shader::{draw_image::DrawGImage, source::Src}, utils::{set_cursor, FloatToVec}, widget_area
+    active_event, animatie_fn, default_handle_animation, event_option, getter, play_animation, ref_play_animation, pure_after_apply, ref_area, ref_event_option, ref_getter_setter, ref_redraw, ref_render, render_after_apply, set_event, set_scope_path, setter, shader::{draw_image::DrawGImage, source::Src}, utils::{set_cursor, BoolToF32, FloatToVec}, widget_area
 };
 
 live_design! {
     link gen_base;
     use link::shaders::*;
+    use link::gen_theme::*;
 
     pub GImageBase = {{GImage}} {
         width: 32.0,
         height: 32.0,
+        placeholder_color: #E4E7EC,
+        animator: {
+            hover = {
+                default: off,
+                off = {
+                    from: {all: Forward {duration: (GLOBAL_DURATION)}}
+                    apply: {
+                        draw_image: {hover: 0.0}
+                    }
+                }
+                on = {
+                    from: {all: Forward {duration: (GLOBAL_DURATION)}}
+                    apply: {
+                        draw_image: {hover: 1.0}
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -32,13 +53,42 @@ live_design! {
 /// The `GImage` component handles scalable images with adjustable rotation, scaling, and visibility, supporting event triggers such as hovering and clicking.
 ///
 /// ## Animation
-/// No animation is available for the `GImage` component.
+/// - **hover.off**: `draw_image.hover` changes to `0.0`
+/// - **hover.on**: `draw_image.hover` changes to `1.0`
+///
+/// The filters (`grayscale`, `brightness`, `contrast`, `saturation`, `blur`, `tint_color`) are changed to
+/// the `hover_*` ones by the hover animation, if a `hover_*` is not set, the filter is not changed.
 ///
 /// ## Event
 /// The `GImage` component can trigger various events in response to user interactions:
 /// - `HoverIn(GImageHoverParam)`: Triggered when the mouse pointer enters the image area.
 /// - `HoverOut(GImageHoverParam)`: Triggered when the mouse pointer leaves the image area.
 /// - `Clicked(GImageClickedParam)`: Triggered when the image is clicked.
+/// - `Loaded(GImageLoadedParam)`: Triggered when the src is loaded.
+/// - `Failed(GImageFailedParam)`: Triggered when the src can not be loaded, the param has the error.
+///
+/// ## Loading
+/// A url is downloaded in another thread, the ui is not blocked.
+/// Until it is loaded, the image is drawn with `placeholder_color` (and the `loading` view if it is set).
+/// If the src can not be loaded, the `error_src` is drawn instead, it should be a local image.
+///
+/// ## Shape
+/// The image is clipped by `border_radius` in the shader, a radius bigger than half of the size draws a circle (avatar).
+/// If `crop` is true, the image covers the whole walk like CSS `object-fit: cover`,
+/// `object_position` (`0.0` - `1.0`, like CSS `object-position`) selects the visible part.
+///
+/// ## Formats
+/// PNG, JPEG, GIF, WebP and BMP are supported, an animated GIF, APNG or WebP is played by the frame delays,
+/// it can be controlled by `play`, `pause` and `looping`. The frames of a downloaded image are decoded in
+/// the download thread.
+///
+/// An SVG path or url is rasterized at its own size to a texture, so it is scaled like a bitmap,
+/// use `GSvg` for an icon which must stay sharp at any size.
+///
+/// ## Cache
+/// If `cache` is true, the decoded texture (or the frames of an animated image) is shared by the images
+/// with the same src by `ImageCacheGlobal`, a url is downloaded only once even if many images are loading it
+/// at the same time.
 ///
 /// ## Props
 /// |macro   |prop             |description                    |type               |default |
@@ -67,6 +117,25 @@ live_design! {
 /// |live    |src               |Image source dependency        |LiveDependency     |        |
 /// |live    |texture           |Texture object                 |Option<Texture>    |None    |
 /// |live    |event_key         |Trigger events when true       |bool               |true    |
+/// |live    |loading           |Loading view drawn while loading|Option<LivePtr>   |None    |
+/// |live    |placeholder_color |Color drawn while loading      |Vec4               |#E4E7EC |
+/// |live    |error_src         |Fallback image if src fails    |Src                |None    |
+/// |live    |cache             |Share the texture by the cache |bool               |true    |
+/// |live    |border_radius     |Radius of the corners          |f32                |0.0     |
+/// |live    |border_width      |Width of the border            |f32                |0.0     |
+/// |live    |border_color      |Color of the border            |Vec4               |#0000   |
+/// |live    |crop              |Cover the walk and crop        |bool               |false   |
+/// |live    |object_position   |Visible part of a cropped image|Vec2               |(0.5, 0.5)|
+/// |live    |grayscale         |Grayscale amount (0.0 - 1.0)   |f32                |0.0     |
+/// |live    |brightness        |Brightness multiplier          |f32                |1.0     |
+/// |live    |contrast          |Contrast multiplier            |f32                |1.0     |
+/// |live    |saturation        |Saturation multiplier          |f32                |1.0     |
+/// |live    |blur              |Blur radius in px              |f32                |0.0     |
+/// |live    |tint_color        |Tint color, alpha is the amount|Vec4               |#0000   |
+/// |live    |hover_grayscale ... hover_tint_color|Filters when hovered|Option<_>   |None    |
+/// |live    |animation_key     |Play the hover animation       |bool               |true    |
+/// |live    |autoplay          |Play the animated image        |bool               |true    |
+/// |live    |looping           |Replay from the first frame    |bool               |true    |
 #[derive(Live, Widget)]
 pub struct GImage {
     #[live(true)]
@@ -101,6 +170,83 @@ pub struct GImage {
     pub event_key: bool,
     #[rust]
     pub scope_path: Option<HeapLiveIdPath>,
+    // loading -----------------
+    #[live]
+    pub loading: Option<LivePtr>,
+    #[live]
+    pub placeholder_color: Vec4,
+    #[live]
+    pub error_src: Src,
+    #[live(true)]
+    pub cache: bool,
+    // shape -------------------
+    #[live(0.0)]
+    pub border_radius: f32,
+    #[live(0.0)]
+    pub border_width: f32,
+    #[live]
+    pub border_color: Vec4,
+    #[live(false)]
+    pub crop: bool,
+    #[live(vec2(0.5, 0.5))]
+    pub object_position: Vec2,
+    // filter ------------------
+    #[live(0.0)]
+    pub grayscale: f32,
+    #[live(1.0)]
+    pub brightness: f32,
+    #[live(1.0)]
+    pub contrast: f32,
+    #[live(1.0)]
+    pub saturation: f32,
+    #[live(0.0)]
+    pub blur: f32,
+    #[live]
+    pub tint_color: Vec4,
+    #[live]
+    pub hover_grayscale: Option<f32>,
+    #[live]
+    pub hover_brightness: Option<f32>,
+    #[live]
+    pub hover_contrast: Option<f32>,
+    #[live]
+    pub hover_saturation: Option<f32>,
+    #[live]
+    pub hover_blur: Option<f32>,
+    #[live]
+    pub hover_tint_color: Option<Vec4>,
+    // animator ----------------
+    #[live(true)]
+    pub animation_key: bool,
+    #[animator]
+    pub animator: Animator,
+    // animation ---------------
+    #[live(true)]
+    pub autoplay: bool,
+    #[live(true)]
+    pub looping: bool,
+    /// frames of an animated image, empty for a still image
+    #[rust]
+    frames: Vec<ImageFrame>,
+    #[rust]
+    frame_index: usize,
+    #[rust]
+    frame_timer: Timer,
+    #[rust]
+    pub playing: bool,
+    #[rust]
+    loading_view: Option<GLoading>,
+    #[rust]
+    pub is_loading: bool,
+    /// the src which is loading, it is sent back with the loaded or failed event
+    #[rust]
+    loading_src: String,
+    /// the download this image is waiting for and its url (also the cache key)
+    #[rust]
+    download: Option<(Downloads, String)>,
+    /// `Loaded` / `Failed` of a src loaded before the first draw, they are sent when the scope path is known
+    #[rust]
+    pending_events: Vec<GImageEvent>,
 }
 
 impl ImageCacheImpl for GImage {
@@ -113,6 +259,12 @@ impl ImageCacheImpl for GImage {
     }
 }
 
+impl Drop for GImage {
+    fn drop(&mut self) {
+        self.leave_download();
+    }
+}
+
 impl LiveHook for GImage {
     // fn after_apply_from_doc(&mut self, cx: &mut Cx) {
     //     if !self.visible {
@@ -139,6 +291,7 @@ impl Widget for GImage {
                 .get_format(cx)
                 .vec_width_height()
                 .unwrap_or((self.min_width as usize, self.min_height as usize));
+            self.draw_image.image_size = vec2(width as f32, height as f32);
             (width as f64 * self.scale, height as f64)
         } else {
             self.draw_image.draw_vars.empty_texture(0);
@@ -146,6 +299,8 @@ impl Widget for GImage {
         };
         let aspect = width / height;
         match self.fit {
+            // a cropped image covers the walk, it is not resized
+            _ if self.crop => {}
             ImageFit::Size => {
                 walk.width = Size::Fixed(width);
                 walk.height = Size::Fixed(height);
@@ -175,34 +330,60 @@ impl Widget for GImage {
             }
         }
         self.draw_walk_rotated_image(cx, walk);
+        if self.is_loading && self.loading.is_some() {
+            let rect = self.area().rect(cx);
+            let loading = self
+                .loading_view
+                .get_or_insert_with(|| GLoading::new_from_ptr(cx, self.loading));
+            cx.begin_turtle(
+                Walk {
+                    abs_pos: Some(rect.pos),
+                    width: Size::Fixed(rect.size.x),
+                    height: Size::Fixed(rect.size.y),
+                    ..Default::default()
+                },
+                Layout {
+                    align: Align { x: 0.5, y: 0.5 },
+                    ..Default::default()
+                },
+            );
+            let walk = loading.walk(cx);
+            let _ = loading.draw_walk(cx, scope, walk);
+            cx.end_turtle();
+        }
         self.set_scope_path(&scope.path);
+        for e in std::mem::take(&mut self.pending_events) {
+            self.emit_load_event(cx, e);
+        }
         DrawStep::done()
     }
     fn handle_event_with(
         &mut self,
         cx: &mut Cx,
         event: &Event,
-        _scope: &mut Scope,
+        scope: &mut Scope,
         sweep_area: Area,
     ) {
         if !self.visible {
             return;
         }
+        self.handle_loading(cx, event, scope);
         let hit = event.hits_with_options(
             cx,
             self.area(),
             HitOptions::new().with_sweep_area(sweep_area),
         );
 
-        self.handle_widget_event(cx, hit, sweep_area)
+        self.handle_widget_event(cx, event, hit, sweep_area)
     }
-    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         if !self.visible {
             return;
         }
+        self.handle_loading(cx, event, scope);
         let focus_area = self.area();
         let hit = event.hits(cx, self.area());
-        self.handle_widget_event(cx, hit, focus_area)
+        self.handle_widget_event(cx, event, hit, focus_area)
     }
     fn is_visible(&self) -> bool {
         self.visible
@@ -212,30 +393,80 @@ impl Widget for GImage {
 impl GImage {
     render_after_apply!("GImage");
     set_scope_path!();
+    play_animation!();
     widget_area! {
         area, draw_image
     }
     active_event! {
         active_hover_in: GImageEvent::HoverIn |e: FingerHoverEvent| => GImageHoverParam{ e },
         active_hover_out: GImageEvent::HoverOut |e: FingerHoverEvent| => GImageHoverParam{ e },
-        active_clicked: GImageEvent::Clicked |e: FingerUpEvent| => GImageClickedParam{ e }
+        active_clicked: GImageEvent::Clicked |e: FingerUpEvent| => GImageClickedParam{ e },
+        active_loaded: GImageEvent::Loaded |e: GImageLoadedParam| => e,
+        active_failed: GImageEvent::Failed |e: GImageFailedParam| => e
     }
     event_option! {
         hover_in: GImageEvent::HoverIn => GImageHoverParam,
         hover_out: GImageEvent::HoverOut => GImageHoverParam,
-        clicked: GImageEvent::Clicked => GImageClickedParam
+        clicked: GImageEvent::Clicked => GImageClickedParam,
+        loaded: GImageEvent::Loaded => GImageLoadedParam,
+        failed: GImageEvent::Failed => GImageFailedParam
     }
     pub fn redraw(&self, cx: &mut Cx) {
         self.draw_image.redraw(cx);
     }
+    /// the hover filters fall back to the normal ones
+    fn render_filter(&mut self) {
+        self.draw_image.grayscale = self.grayscale;
+        self.draw_image.brightness = self.brightness;
+        self.draw_image.contrast = self.contrast;
+        self.draw_image.saturation = self.saturation;
+        self.draw_image.blur = self.blur;
+        self.draw_image.tint_color = self.tint_color;
+        self.draw_image.hover_grayscale = self.hover_grayscale.unwrap_or(self.grayscale);
+        self.draw_image.hover_brightness = self.hover_brightness.unwrap_or(self.brightness);
+        self.draw_image.hover_contrast = self.hover_contrast.unwrap_or(self.contrast);
+        self.draw_image.hover_saturation = self.hover_saturation.unwrap_or(self.saturation);
+        self.draw_image.hover_blur = self.hover_blur.unwrap_or(self.blur);
+        self.draw_image.hover_tint_color = self.hover_tint_color.unwrap_or(self.tint_color);
+    }
+    pub fn clear_animation(&mut self, cx: &mut Cx) {
+        self.draw_image.apply_over(
+            cx,
+            live! {
+                hover: 0.0
+            },
+        );
+    }
+    pub fn animate_hover_on(&mut self, cx: &mut Cx) -> () {
+        self.draw_image.apply_over(
+            cx,
+            live! {
+                hover: 1.0
+            },
+        );
+    }
+    pub fn animate_hover_off(&mut self, cx: &mut Cx) -> () {
+        self.draw_image.apply_over(
+            cx,
+            live! {
+                hover: 0.0
+            },
+        );
+    }
     pub fn render(&mut self, cx: &mut Cx) -> Result<(), Box<dyn std::error::Error>> {
         self.draw_image.apply_over(
             cx,
             live! {
                 image_scale: (self.scale),
                 opacity: (self.opacity),
+                border_radius: (self.border_radius),
+                border_width: (self.border_width),
+                border_color: (self.border_color),
+                object_position: (self.object_position),
+                crop: (self.crop.to_f32()),
             },
         );
+        self.render_filter();
 
         self.lazy_create_image_cache(cx);
         match self.src.clone() {
@@ -258,7 +489,8 @@ impl GImage {
         }
         self.draw_image.draw_walk(cx, walk);
     }
-    pub fn handle_widget_event(&mut self, cx: &mut Cx, hit: Hit, focus_area: Area) {
+    pub fn handle_widget_event(&mut self, cx: &mut Cx, event: &Event, hit: Hit, focus_area: Area) {
+        default_handle_animation!(self, cx, event);
         match hit {
             Hit::FingerDown(_) => {
                 if self.grab_key_focus {
@@ -267,9 +499,11 @@ impl GImage {
             }
             Hit::FingerHoverIn(e) => {
                 let _ = set_cursor(cx, self.cursor.as_ref());
+                self.play_animation(cx, id!(hover.on));
                 self.active_hover_in(cx, e);
             }
             Hit::FingerHoverOut(e) => {
+                self.play_animation(cx, id!(hover.off));
                 self.active_hover_out(cx, e);
             }
             Hit::FingerUp(e) => {
@@ -280,83 +514,319 @@ impl GImage {
             _ => (),
         }
     }
+    /// load the src, a url is loaded in another thread and the result is back by `Loaded` or `Failed` event,
+    /// other srcs are loaded at once and the error is also returned
     pub fn load(&mut self, cx: &mut Cx, src: &str) -> Result<(), Box<dyn std::error::Error>> {
-        /// load from path as u8
-        fn fpath_u8<P>(path: P) -> Result<Vec<u8>, Box<dyn std::error::Error>>
-        where
-            P: AsRef<Path>,
-        {
-            let mut file = File::open(path)?;
-            let mut content: Vec<u8> = vec![];
-            file.read_to_end(&mut content)?;
-            Ok(content)
+        // the same url is loading, do not download it again
+        if self.is_loading && self.loading_src == src {
+            return Ok(());
         }
-
-        fn from_path<P>(
-            img: &mut GImage,
-            cx: &mut Cx,
-            path: P,
-        ) -> Result<(), Box<dyn std::error::Error>>
-        where
-            P: AsRef<Path>,
-        {
-            match imghdr::from_file(path.as_ref())? {
-                Some(ty) => match ty {
-                    imghdr::Type::Png => {
-                        let buf = fpath_u8(path.as_ref())?;
-                        let _ = img.load_png_from_data(cx, &buf, 0)?;
-                        Ok(())
+        self.leave_download();
+        self.pause(cx);
+        self.frames.clear();
+        self.set_loading(cx, false);
+        let src_type = match SrcType::from_str(src) {
+            Ok(src_type) => src_type,
+            Err(e) => return self.after_load(cx, src, Err(e.into())),
+        };
+        let key = src_type.cache_key();
+        if let Some(image) = self.cache.then(|| ImageCacheGlobal::get(cx, &key)).flatten() {
+            self.set_cached(cx, image);
+            return self.after_load(cx, src, Ok(()));
+        }
+        let res = match src_type {
+            SrcType::Url(url) => {
+                self.loading_src = src.to_string();
+                self.load_url(cx, url);
+                return Ok(());
+            }
+            src_type => self.load_local(cx, src_type),
+        };
+        if res.is_ok() {
+            self.cache_image(cx, &key);
+        }
+        self.after_load(cx, src, res)
+    }
+    fn cache_image(&mut self, cx: &mut Cx, key: &str) {
+        if !self.cache {
+            return;
+        }
+        let image = if self.frames.is_empty() {
+            self.texture.clone().map(CachedImage::Still)
+        } else {
+            Some(CachedImage::Animated(Rc::new(self.frames.clone())))
+        };
+        if let Some(image) = image {
+            ImageCacheGlobal::insert(cx, key, image);
+        }
+    }
+    fn set_cached(&mut self, cx: &mut Cx, image: CachedImage) {
+        match image {
+            CachedImage::Still(texture) => self.set_texture(Some(texture), 0),
+            CachedImage::Animated(frames) => self.set_frames(cx, frames.as_ref().clone()),
+        }
+    }
+    /// load a path or base64 src, url is not supported here
+    fn load_local(&mut self, cx: &mut Cx, src_type: SrcType) -> Result<(), Box<dyn std::error::Error>> {
+        match src_type {
+            SrcType::Path(path_buf) => {
+                let buf = std::fs::read(path_buf)?;
+                self.load_from_bytes(cx, &buf)
+            }
+            SrcType::Base64 { data, ty } => self.load_from_type(cx, &data, ty),
+            SrcType::Url(_) => Err(ImageError::UnsupportedFormat.into()),
+        }
+    }
+    fn load_from_bytes(&mut self, cx: &mut Cx, buf: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(ty) = imghdr::from_bytes(buf) else {
+            if is_svg(buf) {
+                let frames = decode_svg(buf)?;
+                self.load_frames(cx, &frames);
+                return Ok(());
+            }
+            return Err(ImageError::UnsupportedFormat.into());
+        };
+        self.load_from_type(cx, buf, ty)
+    }
+    fn load_from_type(
+        &mut self,
+        cx: &mut Cx,
+        buf: &[u8],
+        ty: imghdr::Type,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match ty {
+            imghdr::Type::Png if !is_apng(buf) => {
+                self.load_png_from_data(cx, buf, 0).map_err(|e| e.into())
+            }
+            imghdr::Type::Jpeg => self.load_jpg_from_data(cx, buf, 0).map_err(|e| e.into()),
+            ty => {
+                let frames = decode_frames(buf, ty)?;
+                self.load_frames(cx, &frames);
+                Ok(())
+            }
+        }
+    }
+    fn load_frames(&mut self, cx: &mut Cx, frames: &[DecodedFrame]) {
+        let frames = frames
+            .iter()
+            .map(|frame| ImageFrame {
+                texture: frame.to_texture(cx),
+                delay: frame.delay,
+            })
+            .collect();
+        self.set_frames(cx, frames);
+    }
+    fn set_frames(&mut self, cx: &mut Cx, frames: Vec<ImageFrame>) {
+        self.frames = frames;
+        self.frame_index = 0;
+        let texture = self.frames.first().map(|frame| frame.texture.clone());
+        self.set_texture(texture, 0);
+        if self.frames.len() <= 1 {
+            self.frames.clear();
+        } else if self.autoplay {
+            self.play(cx);
+        }
+    }
+    /// play the animated image, if it is stopped at the last frame, play from the first one
+    pub fn play(&mut self, cx: &mut Cx) {
+        if self.frames.is_empty() || self.playing {
+            return;
+        }
+        if self.frame_index + 1 >= self.frames.len() && !self.looping {
+            self.show_frame(cx, 0);
+        }
+        self.playing = true;
+        self.frame_timer = cx.start_timeout(self.frames[self.frame_index].delay);
+    }
+    pub fn pause(&mut self, cx: &mut Cx) {
+        cx.stop_timer(self.frame_timer);
+        self.playing = false;
+    }
+    fn show_frame(&mut self, cx: &mut Cx, index: usize) {
+        self.frame_index = index;
+        let texture = self.frames[index].texture.clone();
+        self.set_texture(Some(texture), 0);
+        self.redraw(cx);
+    }
+    fn next_frame(&mut self, cx: &mut Cx) {
+        if !self.playing || self.frames.is_empty() {
+            return;
+        }
+        let mut index = self.frame_index + 1;
+        if index >= self.frames.len() {
+            if !self.looping {
+                self.playing = false;
+                return;
+            }
+            index = 0;
+        }
+        self.show_frame(cx, index);
+        self.frame_timer = cx.start_timeout(self.frames[index].delay);
+    }
+    /// ## download in another thread, the ui is signaled when the bytes are back
+    /// the images with `cache` share the download of the same url
+    fn load_url(&mut self, cx: &mut Cx, url: String) {
+        self.set_loading(cx, true);
+        let downloads = if self.cache {
+            ImageCacheGlobal::downloads(cx)
+        } else {
+            Downloads::default()
+        };
+        let start = downloads.join(&url);
+        self.download = Some((downloads.clone(), url.clone()));
+        if !start {
+            // the download may be done already
+            SignalToUI::set_ui_signal();
+            return;
+        }
+        let disk_path = self
+            .cache
+            .then(|| ImageCacheGlobal::disk_path(cx, &url))
+            .flatten();
+        std::thread::spawn(move || {
+            let cached = disk_path.as_ref().and_then(|path| std::fs::read(path).ok());
+            let from_disk = cached.is_some();
+            let buf = match cached {
+                Some(buf) => Ok(buf),
+                None => reqwest::blocking::get(&url)
+                    .and_then(|res| res.error_for_status())
+                    .and_then(|res| res.bytes())
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|e| e.to_string()),
+            };
+            // keep the downloaded bytes, they are written only if they can be decoded
+            let downloaded = disk_path
+                .as_ref()
+                .filter(|_| !from_disk)
+                .and_then(|_| buf.as_ref().ok().cloned());
+            // decode in this thread, the ui thread only creates the textures
+            let prepared = buf.and_then(PreparedImage::prepare);
+            if let Some(path) = disk_path.as_ref() {
+                match (&prepared, downloaded) {
+                    (Ok(_), Some(downloaded)) => {
+                        let _ = write_disk_cache(path, &downloaded);
                     }
-                    imghdr::Type::Jpeg => {
-                        let buf = fpath_u8(path.as_ref())?;
-                        let _ = img.load_jpg_from_data(cx, &buf, 0)?;
+                    (Err(_), _) if from_disk => {
+                        let _ = std::fs::remove_file(path);
+                    }
+                    _ => (),
+                }
+            }
+            downloads.finish(&url, prepared);
+        });
+    }
+    /// take the result of the download, the texture decoded by another image is used if it is cached
+    fn poll_download(&mut self, cx: &mut Cx) {
+        let Some((downloads, url)) = self.download.clone() else {
+            return;
+        };
+        let state = match downloads.state(&url) {
+            Some(DownloadState::Pending) => return,
+            state => state,
+        };
+        self.leave_download();
+        self.set_loading(cx, false);
+        let src = std::mem::take(&mut self.loading_src);
+        let res = match state {
+            Some(DownloadState::Loaded(prepared)) => {
+                match self.cache.then(|| ImageCacheGlobal::get(cx, &url)).flatten() {
+                    Some(image) => {
+                        self.set_cached(cx, image);
                         Ok(())
                     }
-                    _ => return Err(ImageError::UnsupportedFormat.into()),
-                },
-                None => Err(ImageError::UnsupportedFormat.into()),
+                    None => {
+                        let res = match prepared.as_ref() {
+                            PreparedImage::Bytes(buf) => self.load_from_bytes(cx, buf),
+                            PreparedImage::Frames(frames) => {
+                                self.load_frames(cx, frames);
+                                Ok(())
+                            }
+                        };
+                        if res.is_ok() {
+                            self.cache_image(cx, &url);
+                        } else if self.cache {
+                            // the bytes in the disk cache are broken, download them again next time
+                            ImageCacheGlobal::remove_disk(cx, &url);
+                        }
+                        res
+                    }
+                }
             }
+            Some(DownloadState::Failed(e)) => Err(e.into()),
+            _ => Err("the download is lost".into()),
+        };
+        let _ = self.after_load(cx, &src, res);
+    }
+    fn leave_download(&mut self) {
+        if let Some((downloads, url)) = self.download.take() {
+            downloads.leave(&url);
         }
-
-        fn from_bytes(
-            img: &mut GImage,
-            cx: &mut Cx,
-            buf: Vec<u8>,
-        ) -> Result<(), Box<dyn std::error::Error>> {
-            match imghdr::from_bytes(&buf) {
-                Some(ty) => match ty {
-                    imghdr::Type::Png => img.load_png_from_data(cx, &buf, 0).map_err(|e| e.into()),
-                    imghdr::Type::Jpeg => img.load_jpg_from_data(cx, &buf, 0).map_err(|e| e.into()),
-                    _ => Err(ImageError::UnsupportedFormat.into()),
-                },
-                None => Err(ImageError::UnsupportedFormat.into()),
+    }
+    fn after_load(
+        &mut self,
+        cx: &mut Cx,
+        src: &str,
+        res: Result<(), Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match &res {
+            Ok(_) => self.emit_load_event(
+                cx,
+                GImageEvent::Loaded(GImageLoadedParam { src: src.to_string() }),
+            ),
+            Err(e) => {
+                self.emit_load_event(
+                    cx,
+                    GImageEvent::Failed(GImageFailedParam {
+                        src: src.to_string(),
+                        error: e.to_string(),
+                    }),
+                );
+                self.load_fallback(cx);
             }
         }
-
-        let src_type = SrcType::from_str(src)?;
-        let _ = match src_type {
-            SrcType::Path(path_buf) => from_path(self, cx, path_buf),
-            SrcType::Url(url) => {
-                // use reqwest::get do not jam the main thread
-                let (sender, reciver) = std::sync::mpsc::channel();
-                std::thread::spawn(move || {
-                    let buf = reqwest::blocking::get(&url)
-                        .map_err(|e| e.to_string())
-                        .and_then(|res| res.bytes().map_err(|e| e.to_string()))
-                        .map(|bytes| bytes.to_vec());
-                    sender.send(buf).unwrap();
-                });
-                let buf = reciver.recv()??;
-                from_bytes(self, cx, buf)
-            }
-            SrcType::Base64 { data, ty } => match ty {
-                imghdr::Type::Png => self.load_png_from_data(cx, &data, 0).map_err(|e| e.into()),
-                imghdr::Type::Jpeg => self.load_jpg_from_data(cx, &data, 0).map_err(|e| e.into()),
-                _ => Err(ImageError::UnsupportedFormat.into()),
-            },
-        }?;
         self.redraw(cx);
-        Ok(())
+        res
+    }
+    /// a src in DSL is loaded before the first draw, the event waits for the scope path
+    fn emit_load_event(&mut self, cx: &mut Cx, e: GImageEvent) {
+        if self.scope_path.is_none() {
+            self.pending_events.push(e);
+            return;
+        }
+        match e {
+            GImageEvent::Loaded(e) => self.active_loaded(cx, e),
+            GImageEvent::Failed(e) => self.active_failed(cx, e),
+            _ => (),
+        }
+    }
+    /// draw the `error_src` instead of the failed src
+    fn load_fallback(&mut self, cx: &mut Cx) {
+        let _: Result<(), Box<dyn std::error::Error>> = match self.error_src.clone() {
+            Src::None => return,
+            Src::Live(live_dependency) => self
+                .load_image_dep_by_path(cx, live_dependency.as_str(), 0)
+                .map_err(|e| e.into()),
+            src => SrcType::from_str(&src.to_string())
+                .map_err(|e| e.into())
+                .and_then(|src_type| self.load_local(cx, src_type)),
+        };
+    }
+    fn set_loading(&mut self, cx: &mut Cx, is_loading: bool) {
+        self.is_loading = is_loading;
+        self.draw_image.is_loading = is_loading.to_f32();
+        self.draw_image.placeholder_color = self.placeholder_color;
+        self.redraw(cx);
+    }
+    fn handle_loading(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if let Event::Signal = event {
+            self.poll_download(cx);
+        }
+        if self.frame_timer.is_event(event).is_some() {
+            self.next_frame(cx);
+        }
+        if let Some(loading) = self.loading_view.as_mut().filter(|_| self.is_loading) {
+            loading.handle_event(cx, event, scope);
+        }
     }
     setter! {
         GImage{
@@ -380,6 +850,27 @@ impl GImage {
             set_flow(flow: Flow) {|c, _cx|{c.layout.flow = flow; Ok(())}},
             set_spacing(spacing: f64) {|c, _cx|{c.layout.spacing = spacing; Ok(())}},
             set_event_key(event_key: bool) {|c, _cx|{c.event_key = event_key; Ok(())}},
+            set_cache(cache: bool) {|c, _cx|{c.cache = cache; Ok(())}},
+            set_border_radius(border_radius: f32) {|c, _cx|{c.border_radius = border_radius; c.draw_image.border_radius = border_radius; Ok(())}},
+            set_border_width(border_width: f32) {|c, _cx|{c.border_width = border_width; c.draw_image.border_width = border_width; Ok(())}},
+            set_border_color(border_color: Vec4) {|c, _cx|{c.border_color = border_color; c.draw_image.border_color = border_color; Ok(())}},
+            set_crop(crop: bool) {|c, _cx|{c.crop = crop; c.draw_image.crop = crop.to_f32(); Ok(())}},
+            set_object_position(object_position: Vec2) {|c, _cx|{c.object_position = object_position; c.draw_image.object_position = object_position; Ok(())}},
+            set_grayscale(grayscale: f32) {|c, _cx|{c.grayscale = grayscale; c.render_filter(); Ok(())}},
+            set_brightness(brightness: f32) {|c, _cx|{c.brightness = brightness; c.render_filter(); Ok(())}},
+            set_contrast(contrast: f32) {|c, _cx|{c.contrast = contrast; c.render_filter(); Ok(())}},
+            set_saturation(saturation: f32) {|c, _cx|{c.saturation = saturation; c.render_filter(); Ok(())}},
+            set_blur(blur: f32) {|c, _cx|{c.blur = blur; c.render_filter(); Ok(())}},
+            set_tint_color(tint_color: Vec4) {|c, _cx|{c.tint_color = tint_color; c.render_filter(); Ok(())}},
+            set_hover_grayscale(grayscale: f32) {|c, _cx|{c.hover_grayscale.replace(grayscale); c.render_filter(); Ok(())}},
+            set_hover_brightness(brightness: f32) {|c, _cx|{c.hover_brightness.replace(brightness); c.render_filter(); Ok(())}},
+            set_hover_contrast(contrast: f32) {|c, _cx|{c.hover_contrast.replace(contrast); c.render_filter(); Ok(())}},
+            set_hover_saturation(saturation: f32) {|c, _cx|{c.hover_saturation.replace(saturation); c.render_filter(); Ok(())}},
+            set_hover_blur(blur: f32) {|c, _cx|{c.hover_blur.replace(blur); c.render_filter(); Ok(())}},
+            set_hover_tint_color(tint_color: Vec4) {|c, _cx|{c.hover_tint_color.replace(tint_color); c.render_filter(); Ok(())}},
+            set_animation_key(animation_key: bool) {|c, _cx|{c.animation_key = animation_key; Ok(())}},
+            set_autoplay(autoplay: bool) {|c, _cx|{c.autoplay = autoplay; Ok(())}},
+            set_looping(looping: bool) {|c, _cx|{c.looping = looping; Ok(())}},
             set_src(src: String) {|c, cx| c.load(cx, &src)}
         }
     }
@@ -405,6 +896,27 @@ impl GImage {
             get_flow(Flow) {|c| {c.layout.flow}},
             get_spacing(f64) {|c| {c.layout.spacing}},
             get_event_key(bool) {|c| c.event_key},
+            get_cache(bool) {|c| c.cache},
+            get_border_radius(f32) {|c| c.border_radius},
+            get_border_width(f32) {|c| c.border_width},
+            get_border_color(Vec4) {|c| c.border_color},
+            get_crop(bool) {|c| c.crop},
+            get_object_position(Vec2) {|c| c.object_position},
+            get_grayscale(f32) {|c| c.grayscale},
+            get_brightness(f32) {|c| c.brightness},
+            get_contrast(f32) {|c| c.contrast},
+            get_saturation(f32) {|c| c.saturation},
+            get_blur(f32) {|c| c.blur},
+            get_tint_color(Vec4) {|c| c.tint_color},
+            get_hover_grayscale(f32) {|c| c.draw_image.hover_grayscale},
+            get_hover_brightness(f32) {|c| c.draw_image.hover_brightness},
+            get_hover_contrast(f32) {|c| c.draw_image.hover_contrast},
+            get_hover_saturation(f32) {|c| c.draw_image.hover_saturation},
+            get_hover_blur(f32) {|c| c.draw_image.hover_blur},
+            get_hover_tint_color(Vec4) {|c| c.draw_image.hover_tint_color},
+            get_animation_key(bool) {|c| c.animation_key},
+            get_autoplay(bool) {|c| c.autoplay},
+            get_looping(bool) {|c| c.looping},
             get_src(Src) {|c| c.src.clone()}
         }
     }
@@ -431,7 +943,28 @@ impl GImageRef {
         get_align, set_align -> Align,
         get_flow, set_flow -> Flow,
         get_spacing, set_spacing -> f64,
-        get_event_key, set_event_key -> bool
+        get_event_key, set_event_key -> bool,
+        get_cache, set_cache -> bool,
+        get_border_radius, set_border_radius -> f32,
+        get_border_width, set_border_width -> f32,
+        get_border_color, set_border_color -> Vec4,
+        get_crop, set_crop -> bool,
+        get_object_position, set_object_position -> Vec2,
+        get_grayscale, set_grayscale -> f32,
+        get_brightness, set_brightness -> f32,
+        get_contrast, set_contrast -> f32,
+        get_saturation, set_saturation -> f32,
+        get_blur, set_blur -> f32,
+        get_tint_color, set_tint_color -> Vec4,
+        get_hover_grayscale, set_hover_grayscale -> f32,
+        get_hover_brightness, set_hover_brightness -> f32,
+        get_hover_contrast, set_hover_contrast -> f32,
+        get_hover_saturation, set_hover_saturation -> f32,
+        get_hover_blur, set_hover_blur -> f32,
+        get_hover_tint_color, set_hover_tint_color -> Vec4,
+        get_animation_key, set_animation_key -> bool,
+        get_autoplay, set_autoplay -> bool,
+        get_looping, set_looping -> bool
     }
     pub fn set_src(&self, cx: &mut Cx, src: String) -> Result<(), Box<dyn std::error::Error>> {
         self.load(cx, &src)
@@ -497,10 +1030,37 @@ impl GImageRef {
     ref_redraw!();
     ref_area!();
     ref_render!();
+    animatie_fn! {
+        clear_animation,
+        animate_hover_on,
+        animate_hover_off
+    }
+    ref_play_animation! {
+        play_hover_on: id!(hover.on),
+        play_hover_off: id!(hover.off)
+    }
     ref_event_option! {
         hover_in => GImageHoverParam,
         hover_out => GImageHoverParam,
-        clicked => GImageClickedParam
+        clicked => GImageClickedParam,
+        loaded => GImageLoadedParam,
+        failed => GImageFailedParam
+    }
+    pub fn is_loading(&self) -> bool {
+        self.borrow().map_or(false, |c_ref| c_ref.is_loading)
+    }
+    pub fn play(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.play(cx);
+        }
+    }
+    pub fn pause(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.pause(cx);
+        }
+    }
+    pub fn is_playing(&self) -> bool {
+        self.borrow().map_or(false, |c_ref| c_ref.playing)
     }
 }
 
@@ -508,7 +1068,9 @@ impl GImageSet {
     set_event! {
         hover_in => GImageHoverParam,
         hover_out => GImageHoverParam,
-        clicked => GImageClickedParam
+        clicked => GImageClickedParam,
+        loaded => GImageLoadedParam,
+        failed => GImageFailedParam
     }
 }
 
@@ -518,6 +1080,17 @@ pub enum SrcType {
     Base64 { data: Vec<u8>, ty: imghdr::Type },
 }
 
+impl SrcType {
+    /// key of the src in `ImageCacheGlobal`, base64 data is hashed
+    pub fn cache_key(&self) -> String {
+        match self {
+            SrcType::Path(path_buf) => path_buf.display().to_string(),
+            SrcType::Url(url) => url.to_string(),
+            SrcType::Base64 { data, .. } => format!("base64:{}", hash_key(data)),
+        }
+    }
+}
+
 impl FromStr for SrcType {
     type Err = ImageError;
 
@@ -531,6 +1104,9 @@ impl FromStr for SrcType {
                 |ty| match *ty {
                     "data:image/png;base64" => Ok(imghdr::Type::Png),
                     "data:image/jpeg;base64" => Ok(imghdr::Type::Jpeg),
+                    "data:image/webp;base64" => Ok(imghdr::Type::Webp),
+                    "data:image/gif;base64" => Ok(imghdr::Type::Gif),
+                    "data:image/bmp;base64" => Ok(imghdr::Type::Bmp),
                     _ => return Err(ImageError::UnsupportedFormat),
                 },
             )?;
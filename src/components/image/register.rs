@@ -2,4 +2,5 @@ use makepad_widgets::Cx;
 
 pub fn register(cx: &mut Cx) {
     crate::components::image::live_design(cx);
+    crate::components::image::viewer::live_design(cx);
 }
\ No newline at end of file
@@ -0,0 +1,344 @@
+use makepad_widgets::*;
+
+use crate::{event_option, ref_event_option, set_event};
+
+use super::{GImage, GImageViewerEvent, GImageViewerZoomedParam};
+
+live_design! {
+    link gen_base;
+
+    pub GImageViewerBase = {{GImageViewer}}{
+        width: Fill,
+        height: Fill,
+        fit: Smallest,
+        placeholder_color: #E4E7EC,
+        // the hover animator of `GImageBase` is not inherited
+        animation_key: false,
+    }
+}
+
+/// # GImageViewer Component
+/// A `GImage` which can be zoomed and panned, used to preview screenshots and diagrams.
+/// - mouse wheel or pinch: zoom around the cursor (the center of the fingers)
+/// - drag: pan, the view never leaves the image
+/// - double click: toggle between fit (`zoom = 1.0`) and the actual size (100%), the actual size is
+///   reached even if it is out of `min_zoom` / `max_zoom` (an image smaller than the view)
+///
+/// All props of `GImage` can be used.
+///
+/// ## Event
+/// - `Zoomed(GImageViewerZoomedParam)`: Triggered when the zoom is changed, also by `reset`.
+///
+/// ## Props
+/// |macro   |prop             |description                    |type               |default |
+/// |--------|-----------------|-------------------------------|-------------------|--------|
+/// |live    |min_zoom         |Minimum zoom, `1.0` is fit     |f64                |1.0     |
+/// |live    |max_zoom         |Maximum zoom                   |f64                |8.0     |
+/// |live    |zoom_step        |Zoom step of a wheel scroll    |f64                |0.1     |
+#[derive(Live, Widget)]
+pub struct GImageViewer {
+    #[deref]
+    pub image: GImage,
+    #[live(1.0)]
+    pub min_zoom: f64,
+    #[live(8.0)]
+    pub max_zoom: f64,
+    #[live(0.1)]
+    pub zoom_step: f64,
+    #[rust(1.0)]
+    pub zoom: f64,
+    /// the texture position (0.0 - 1.0) at the top left of the view
+    #[rust]
+    pub pan: DVec2,
+    #[rust]
+    drag_last: Option<DVec2>,
+    /// distance of the two fingers
+    #[rust]
+    pinch: Option<f64>,
+    /// dpi factor of the last draw, `actual_zoom` is called out of drawing
+    #[rust(1.0)]
+    dpi: f64,
+}
+
+impl LiveHook for GImageViewer {}
+
+impl Widget for GImageViewer {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.apply_view();
+        self.dpi = cx.current_dpi_factor();
+        self.image.draw_walk(cx, scope, walk)
+    }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.image.handle_event(cx, event, scope);
+        if !self.image.visible {
+            return;
+        }
+        let uid = self.widget_uid();
+        if let Event::TouchUpdate(e) = event {
+            self.handle_pinch(cx, uid, &scope.path, e);
+        }
+        match event.hits(cx, self.image.area()) {
+            Hit::FingerScroll(e) => {
+                if e.scroll.y != 0.0 {
+                    let factor = if e.scroll.y < 0.0 {
+                        1.0 + self.zoom_step
+                    } else {
+                        1.0 / (1.0 + self.zoom_step)
+                    };
+                    self.zoom_at(cx, uid, &scope.path, self.zoom * factor, e.abs);
+                }
+            }
+            Hit::FingerDown(e) => {
+                if e.tap_count == 2 {
+                    let zoom = if (self.zoom - 1.0).abs() > f64::EPSILON {
+                        1.0
+                    } else {
+                        self.actual_zoom(cx)
+                    };
+                    self.zoom_at(cx, uid, &scope.path, zoom, e.abs);
+                }
+                self.drag_last.replace(e.abs);
+            }
+            Hit::FingerMove(e) => {
+                if let Some(last) = self.drag_last.filter(|_| self.pinch.is_none()) {
+                    let size = self.image.area().rect(cx).size;
+                    if size.x > 0.0 && size.y > 0.0 {
+                        let delta = e.abs - last;
+                        let pan = dvec2(
+                            self.pan.x - delta.x / size.x / self.zoom,
+                            self.pan.y - delta.y / size.y / self.zoom,
+                        );
+                        self.pan = clamp_pan(pan, self.zoom);
+                        self.redraw(cx);
+                    }
+                }
+                self.drag_last.replace(e.abs);
+            }
+            Hit::FingerUp(_) => {
+                self.drag_last = None;
+            }
+            _ => (),
+        }
+    }
+    fn is_visible(&self) -> bool {
+        self.image.visible
+    }
+}
+
+impl GImageViewer {
+    event_option! {
+        zoomed: GImageViewerEvent::Zoomed => GImageViewerZoomedParam
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        self.image.redraw(cx);
+    }
+    /// the zoom which draws one texture pixel as one physical pixel
+    pub fn actual_zoom(&self, cx: &mut Cx) -> f64 {
+        let size = self.image.area().rect(cx).size;
+        self.image
+            .texture
+            .as_ref()
+            .and_then(|texture| texture.get_format(cx).vec_width_height())
+            .map_or(1.0, |(width, height)| {
+                actual_zoom(
+                    dvec2(width as f64, height as f64),
+                    size * self.dpi,
+                    self.image.crop,
+                )
+            })
+    }
+    /// zoom and keep the texture position under `abs`
+    pub fn zoom_at(
+        &mut self,
+        cx: &mut Cx,
+        uid: WidgetUid,
+        path: &HeapLiveIdPath,
+        zoom: f64,
+        abs: DVec2,
+    ) {
+        let (min, max) = zoom_bounds(self.min_zoom, self.max_zoom, self.actual_zoom(cx));
+        let zoom = zoom.clamp(min, max);
+        let rect = self.image.area().rect(cx);
+        if zoom == self.zoom || rect.size.x <= 0.0 || rect.size.y <= 0.0 {
+            return;
+        }
+        let local = dvec2(
+            (abs.x - rect.pos.x) / rect.size.x,
+            (abs.y - rect.pos.y) / rect.size.y,
+        );
+        self.pan = clamp_pan(zoom_pan(self.pan, local, self.zoom, zoom), zoom);
+        self.zoom = zoom;
+        self.redraw(cx);
+        self.emit_zoomed(cx, uid, path);
+    }
+    fn emit_zoomed(&self, cx: &mut Cx, uid: WidgetUid, path: &HeapLiveIdPath) {
+        if self.image.event_key {
+            cx.widget_action(
+                uid,
+                path,
+                GImageViewerEvent::Zoomed(GImageViewerZoomedParam {
+                    zoom: self.zoom,
+                    pan: self.pan,
+                }),
+            );
+        }
+    }
+    /// zoom around the center of the view
+    pub fn set_zoom(&mut self, cx: &mut Cx, zoom: f64) {
+        let uid = self.widget_uid();
+        let center = self.image.area().rect(cx).center();
+        // not drawn yet, nothing to zoom
+        if let Some(path) = self.image.scope_path.clone() {
+            self.zoom_at(cx, uid, &path, zoom, center);
+        }
+    }
+    /// back to fit
+    pub fn reset(&mut self, cx: &mut Cx) {
+        let changed = self.zoom != 1.0 || self.pan != DVec2::default();
+        self.zoom = 1.0;
+        self.pan = DVec2::default();
+        self.redraw(cx);
+        // not drawn yet, nobody can listen
+        if let Some(path) = self.image.scope_path.clone().filter(|_| changed) {
+            let uid = self.widget_uid();
+            self.emit_zoomed(cx, uid, &path);
+        }
+    }
+    fn handle_pinch(
+        &mut self,
+        cx: &mut Cx,
+        uid: WidgetUid,
+        path: &HeapLiveIdPath,
+        e: &TouchUpdateEvent,
+    ) {
+        if e.touches.len() != 2 {
+            self.pinch = None;
+            return;
+        }
+        let (a, b) = (e.touches[0].abs, e.touches[1].abs);
+        let center = (a + b) / 2.0;
+        if self.pinch.is_none() && !self.image.area().rect(cx).contains(center) {
+            return;
+        }
+        let distance = (a - b).length();
+        if let Some(last) = self.pinch.filter(|last| *last > 0.0) {
+            self.zoom_at(cx, uid, path, self.zoom * distance / last, center);
+        }
+        self.pinch.replace(distance);
+    }
+    fn apply_view(&mut self) {
+        let scale = (1.0 / self.zoom) as f32;
+        self.image.draw_image.image_scale = vec2(scale, scale);
+        self.image.draw_image.image_pan = vec2(self.pan.x as f32, self.pan.y as f32);
+    }
+}
+
+impl GImageViewerRef {
+    pub fn zoom(&self) -> f64 {
+        self.borrow().map_or(1.0, |c_ref| c_ref.zoom)
+    }
+    pub fn set_zoom(&self, cx: &mut Cx, zoom: f64) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_zoom(cx, zoom);
+        }
+    }
+    pub fn reset(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.reset(cx);
+        }
+    }
+    pub fn image(&self) -> Option<std::cell::RefMut<'_, GImage>> {
+        self.borrow_mut()
+            .map(|c_ref| std::cell::RefMut::map(c_ref, |c_ref| &mut c_ref.image))
+    }
+    ref_event_option! {
+        zoomed => GImageViewerZoomedParam
+    }
+}
+
+impl GImageViewerSet {
+    set_event! {
+        zoomed => GImageViewerZoomedParam
+    }
+}
+
+/// the pan which keeps the texture position at `local` (0.0 - 1.0 in the view) after zooming
+pub fn zoom_pan(pan: DVec2, local: DVec2, from: f64, to: f64) -> DVec2 {
+    dvec2(
+        pan.x + local.x / from - local.x / to,
+        pan.y + local.y / from - local.y / to,
+    )
+}
+
+/// the zoom which draws one texture pixel as one pixel of `view` (physical size of the walk),
+/// a stretched texture is scaled on both axes, no axis is drawn smaller than the texture,
+/// a cropped texture covers the view with a single scale
+pub fn actual_zoom(texture: DVec2, view: DVec2, crop: bool) -> f64 {
+    if texture.x <= 0.0 || texture.y <= 0.0 || view.x <= 0.0 || view.y <= 0.0 {
+        return 1.0;
+    }
+    let (x, y) = (texture.x / view.x, texture.y / view.y);
+    if crop {
+        x.min(y)
+    } else {
+        x.max(y)
+    }
+}
+
+/// the zoom range, it is widened to hold the actual size (`actual`) so the double click can reach it
+pub fn zoom_bounds(min: f64, max: f64, actual: f64) -> (f64, f64) {
+    (min.min(actual), max.max(min).max(actual))
+}
+
+/// keep the view in the texture, if the texture is smaller than the view, it is centered
+pub fn clamp_pan(pan: DVec2, zoom: f64) -> DVec2 {
+    let clamp = |v: f64| {
+        let max = 1.0 - 1.0 / zoom;
+        if max < 0.0 {
+            max / 2.0
+        } else {
+            v.clamp(0.0, max)
+        }
+    };
+    dvec2(clamp(pan.x), clamp(pan.y))
+}
+
+#[cfg(test)]
+mod test_viewer {
+    use makepad_widgets::dvec2;
+
+    use super::{actual_zoom, clamp_pan, zoom_bounds, zoom_pan};
+
+    #[test]
+    fn zoom_around_point() {
+        // zoom in at the center, the center is still the center
+        let pan = zoom_pan(dvec2(0.0, 0.0), dvec2(0.5, 0.5), 1.0, 2.0);
+        assert_eq!(pan, dvec2(0.25, 0.25));
+        // zoom in at the top left, nothing moves
+        let pan = zoom_pan(dvec2(0.0, 0.0), dvec2(0.0, 0.0), 1.0, 4.0);
+        assert_eq!(pan, dvec2(0.0, 0.0));
+        // out of the texture is clamped
+        assert_eq!(clamp_pan(dvec2(0.9, -0.1), 2.0), dvec2(0.5, 0.0));
+        assert_eq!(clamp_pan(dvec2(0.3, 0.3), 1.0), dvec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn actual_zoom_of_view() {
+        // dpi 2.0, a 400x200 walk is 800x400 physical pixels
+        assert_eq!(actual_zoom(dvec2(1600.0, 800.0), dvec2(800.0, 400.0), false), 2.0);
+        // stretched into a square, the wider axis decides
+        assert_eq!(actual_zoom(dvec2(1600.0, 800.0), dvec2(400.0, 400.0), false), 4.0);
+        // cropped into a square, the covering scale decides
+        assert_eq!(actual_zoom(dvec2(1600.0, 800.0), dvec2(400.0, 400.0), true), 2.0);
+        assert_eq!(actual_zoom(dvec2(1600.0, 800.0), dvec2(0.0, 400.0), false), 1.0);
+    }
+
+    #[test]
+    fn zoom_bounds_hold_actual_size() {
+        assert_eq!(zoom_bounds(1.0, 8.0, 2.0), (1.0, 8.0));
+        // an image smaller than the view, 100% is below fit
+        assert_eq!(zoom_bounds(1.0, 8.0, 0.25), (0.25, 8.0));
+        assert_eq!(zoom_bounds(1.0, 8.0, 16.0), (1.0, 16.0));
+        assert_eq!(zoom_bounds(2.0, 1.0, 1.5), (1.5, 2.0));
+    }
+}
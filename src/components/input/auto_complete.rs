@@ -0,0 +1,500 @@
+use std::rc::Rc;
+
+use makepad_widgets::*;
+
+use crate::{
+    event_option, pure_after_apply, ref_event_option, render_after_apply, set_event,
+    shader::{draw_text::DrawGText, draw_view::DrawGView},
+    themes::Themes,
+    utils::{get_font_family, BoolToF32, ThemeColor},
+};
+
+use super::{
+    super::{
+        drop_down::PopupMenuGlobal,
+        popup::{GPopup, PopupStackGlobal},
+    },
+    types::{fuzzy_filter, FuzzyMatch},
+    GAutoCompleteEvent, GAutoCompleteSelectedParam, GInput, GInputChangedParam, InputEventType,
+};
+
+live_design! {
+    link gen_base;
+    use link::shaders::*;
+    use link::gen_theme::*;
+
+    pub GAutoCompleteBase = {{GAutoComplete}}{
+        height: 36.0,
+        width: 180.0,
+        font_family: (FONT_FAMILY),
+        font_size: (FONT_SIZE),
+        item_height: 32.0,
+        item_padding: {left: 8.0, right: 8.0},
+        max_items: 8,
+        draw_item: {
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    0.0,
+                    0.0,
+                    self.rect_size.x,
+                    self.rect_size.y,
+                    self.border_radius
+                );
+                sdf.fill(self.get_background_color());
+                return sdf.result;
+            }
+        }
+        draw_item_text: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+        draw_match_text: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+    }
+}
+
+/// A provider to get suggestions dynamically, the arg is the current input text
+pub type AutoCompleteProvider = Rc<dyn Fn(&str) -> Vec<String>>;
+
+/// # GAutoComplete
+/// A `GInput` with a suggestion list.
+/// As the user types, the suggestions (from `suggestions` or from a provider closure) are fuzzy matched
+/// and displayed in a popup under the input, the matched chars are highlighted.
+///
+/// - `Up`/`Down`: move the highlighted item
+/// - `Enter`: select the highlighted item
+/// - `Esc`: close the suggestion list
+///
+/// ## Example
+/// ```rust
+/// let ac = self.ui.gauto_complete(id!(ac));
+/// ac.set_provider(|text| search_city(text));
+///
+/// if let Some(e) = ac.selected(&actions) {
+///     log!("{} selected", e.text);
+/// }
+/// ```
+#[derive(Live, Widget)]
+pub struct GAutoComplete {
+    #[live]
+    pub theme: Themes,
+    #[live]
+    pub input: GInput,
+    #[live]
+    pub popup: Option<LivePtr>,
+    #[live]
+    pub suggestions: Vec<String>,
+    #[live(8)]
+    pub max_items: usize,
+    #[live(32.0)]
+    pub item_height: f64,
+    #[live]
+    pub item_padding: Padding,
+    #[live(4.0)]
+    pub offset: f32,
+    #[live]
+    pub color: Option<Vec4>,
+    #[live]
+    pub match_color: Option<Vec4>,
+    #[live]
+    pub item_background_color: Option<Vec4>,
+    #[live]
+    pub item_hover_color: Option<Vec4>,
+    #[live(2.0)]
+    pub item_border_radius: f32,
+    #[live(10.0)]
+    pub font_size: f64,
+    #[live]
+    pub font_family: LiveDependency,
+    #[redraw]
+    #[live]
+    pub draw_item: DrawGView,
+    #[live]
+    pub draw_item_text: DrawGText,
+    #[live]
+    pub draw_match_text: DrawGText,
+    #[walk]
+    pub walk: Walk,
+    #[layout]
+    pub layout: Layout,
+    #[live(true)]
+    pub visible: bool,
+    #[live(true)]
+    pub event_key: bool,
+    #[rust]
+    pub opened: bool,
+    #[rust]
+    pub matches: Vec<FuzzyMatch>,
+    #[rust]
+    pub highlighted: Option<usize>,
+    #[rust]
+    pub provider: Option<AutoCompleteProvider>,
+    #[rust]
+    item_areas: Vec<Area>,
+}
+
+impl LiveHook for GAutoComplete {
+    fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if self.popup.is_none() || !apply.from.is_from_doc() {
+            return;
+        }
+        let global = cx.global::<PopupMenuGlobal>().clone();
+        let mut global_map = global.map.borrow_mut();
+        global_map.retain(|k, _| cx.live_registry.borrow().generation_valid(*k));
+        let popup = self.popup.unwrap();
+        global_map.get_or_insert(cx, popup, |cx| GPopup::new_from_ptr(cx, Some(popup)));
+    }
+    pure_after_apply!();
+}
+
+impl Widget for GAutoComplete {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        if !self.visible {
+            return DrawStep::done();
+        }
+        let _ = self.input.draw_walk(cx, scope, walk);
+
+        if self.opened && !self.matches.is_empty() && self.popup.is_some() {
+            let global = cx.global::<PopupMenuGlobal>().clone();
+            let mut map = global.map.borrow_mut();
+            let popup = map.get_mut(&self.popup.unwrap()).unwrap();
+            let area = self.input.area().rect(cx);
+            // the suggestion list always has the same width as the input
+            popup.walk.width = Size::Fixed(area.size.x);
+            popup.raise = PopupStackGlobal::take_raise(cx, self.widget_uid());
+            popup.begin(cx);
+            self.draw_items(cx);
+            popup.end(
+                cx,
+                scope,
+                self.input.area(),
+                DVec2 {
+                    x: 0.0,
+                    y: area.size.y + self.offset as f64,
+                },
+            );
+            PopupStackGlobal::set_popup_area(cx, self.widget_uid(), popup.area());
+        }
+
+        DrawStep::done()
+    }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.visible {
+            return;
+        }
+        let uid = self.widget_uid();
+        // a popup opened above the suggestions takes the keys and the pointer events over it
+        if !PopupStackGlobal::accepts(cx, uid, event) {
+            return;
+        }
+        // keyboard navigation should be handled before the input, otherwise the input will move the cursor
+        if self.opened && cx.has_key_focus(self.input.area()) {
+            if let Event::KeyDown(e) = event {
+                match e.key_code {
+                    KeyCode::ArrowDown => {
+                        self.move_highlight(cx, 1);
+                        return;
+                    }
+                    KeyCode::ArrowUp => {
+                        self.move_highlight(cx, -1);
+                        return;
+                    }
+                    KeyCode::ReturnKey if !e.modifiers.shift && self.highlighted.is_some() => {
+                        let index = self.highlighted.unwrap();
+                        self.select(cx, &scope.path, index);
+                        return;
+                    }
+                    KeyCode::Escape => {
+                        self.close(cx);
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if self.opened {
+            for index in 0..self.item_areas.len() {
+                match event.hits(cx, self.item_areas[index]) {
+                    Hit::FingerHoverIn(_) => {
+                        cx.set_cursor(MouseCursor::Hand);
+                        self.highlighted = Some(index);
+                        self.redraw_popup(cx);
+                    }
+                    Hit::FingerUp(e) => {
+                        if e.is_over {
+                            self.select(cx, &scope.path, index);
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if let Event::MouseDown(e) = event {
+                let global = cx.global::<PopupMenuGlobal>().clone();
+                let map = global.map.borrow();
+                let is_in = self
+                    .popup
+                    .as_ref()
+                    .and_then(|popup| map.get(popup))
+                    .map_or(false, |popup| popup.menu_contains_pos(cx, e.abs));
+                drop(map);
+                if !is_in && !self.input.area().rect(cx).contains(e.abs) {
+                    self.close(cx);
+                }
+            }
+        }
+
+        let actions = cx.capture_actions(|cx| self.input.handle_event(cx, event, scope));
+        if let Some(param) = self.input.changed(&actions) {
+            match param.ty {
+                InputEventType::KeyDown(KeyCode::ReturnKey) => {}
+                _ => {
+                    self.update_matches(cx, &param.text);
+                }
+            }
+            if self.event_key {
+                cx.widget_action(uid, &scope.path, GAutoCompleteEvent::Changed(param));
+            }
+        }
+    }
+    fn text(&self) -> String {
+        self.input.text.to_string()
+    }
+    fn set_text(&mut self, cx: &mut Cx, v: &str) {
+        Widget::set_text(&mut self.input, cx, v);
+        self.close(cx);
+    }
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl GAutoComplete {
+    render_after_apply!("GAutoComplete");
+    event_option! {
+        changed: GAutoCompleteEvent::Changed => GInputChangedParam,
+        selected: GAutoCompleteEvent::Selected => GAutoCompleteSelectedParam
+    }
+    pub fn render(&mut self, cx: &mut Cx) -> Result<(), Box<dyn std::error::Error>> {
+        let color = self.color.get(self.theme, 800);
+        let match_color = self.match_color.get(self.theme, 500);
+        let item_background_color = self.item_background_color.use_or("#FFFFFF00")?;
+        let item_hover_color = self.item_hover_color.get(self.theme, 50);
+        self.draw_item.apply_over(
+            cx,
+            live! {
+                background_color: (item_background_color),
+                background_visible: (true.to_f32()),
+                hover_color: (item_hover_color),
+                focus_color: (item_hover_color),
+                border_radius: (self.item_border_radius),
+            },
+        );
+        self.draw_item_text.apply_over(
+            cx,
+            live! {
+                color: (color),
+                text_style: {
+                    font_size: (self.font_size),
+                }
+            },
+        );
+        self.draw_match_text.apply_over(
+            cx,
+            live! {
+                color: (match_color),
+                text_style: {
+                    font_size: (self.font_size),
+                }
+            },
+        );
+        Ok(())
+    }
+    fn draw_items(&mut self, cx: &mut Cx2d) {
+        let _ = get_font_family(&self.font_family, cx, &mut self.draw_item_text.text_style.font);
+        let _ = get_font_family(&self.font_family, cx, &mut self.draw_match_text.text_style.font);
+        let layout = Layout {
+            flow: Flow::Right,
+            align: Align { x: 0.0, y: 0.5 },
+            padding: self.item_padding,
+            ..Default::default()
+        };
+        let walk = Walk {
+            width: Size::Fill,
+            height: Size::Fixed(self.item_height),
+            ..Default::default()
+        };
+        self.item_areas.clear();
+        for (index, item) in self.matches.iter().enumerate() {
+            self.draw_item.focus = (self.highlighted == Some(index)).to_f32();
+            self.draw_item.begin(cx, walk, layout);
+            for (segment, is_matched) in item.segments() {
+                if is_matched {
+                    self.draw_match_text
+                        .draw_walk(cx, Walk::fit(), Align::default(), segment);
+                } else {
+                    self.draw_item_text
+                        .draw_walk(cx, Walk::fit(), Align::default(), segment);
+                }
+            }
+            self.draw_item.end(cx);
+            self.item_areas.push(self.draw_item.area());
+        }
+    }
+    /// ## update the suggestion list by the input text
+    /// if a provider is set, the candidates come from the provider, otherwise from `suggestions`
+    pub fn update_matches(&mut self, cx: &mut Cx, text: &str) {
+        self.matches = if let Some(provider) = self.provider.as_ref() {
+            fuzzy_filter(text, provider(text).iter(), self.max_items)
+        } else {
+            fuzzy_filter(text, self.suggestions.iter(), self.max_items)
+        };
+        self.highlighted = None;
+        if self.matches.is_empty() {
+            self.close(cx);
+        } else {
+            self.open(cx);
+        }
+    }
+    fn move_highlight(&mut self, cx: &mut Cx, step: isize) {
+        let len = self.matches.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let next = match self.highlighted {
+            Some(index) => (index as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        self.highlighted = Some(next as usize);
+        self.redraw_popup(cx);
+    }
+    fn select(&mut self, cx: &mut Cx, path: &HeapLiveIdPath, index: usize) {
+        let Some(item) = self.matches.get(index) else {
+            return;
+        };
+        let text = item.text.to_string();
+        // the widget `set_text` filters the text and clears the history, so the typed query can not be undone on the suggestion
+        Widget::set_text(&mut self.input, cx, &text);
+        self.input.set_input_cursor(Cursor {
+            head: IndexAffinity {
+                index: text.len(),
+                affinity: Affinity::After,
+            },
+            tail: IndexAffinity {
+                index: text.len(),
+                affinity: Affinity::After,
+            },
+        });
+        self.input.force_new_edit_group();
+        self.close(cx);
+        if self.event_key {
+            cx.widget_action(
+                self.widget_uid(),
+                path,
+                GAutoCompleteEvent::Selected(GAutoCompleteSelectedParam { text, index }),
+            );
+        }
+    }
+    /// the suggestions are a layer of the popup stack without a sweep lock, the input keeps getting the events
+    pub fn open(&mut self, cx: &mut Cx) {
+        if !self.opened {
+            PopupStackGlobal::open(cx, self.widget_uid(), Area::Empty);
+        }
+        self.opened = true;
+        self.redraw_popup(cx);
+    }
+    pub fn close(&mut self, cx: &mut Cx) {
+        if !self.opened {
+            return;
+        }
+        PopupStackGlobal::close(cx, self.widget_uid());
+        self.opened = false;
+        self.highlighted = None;
+        self.item_areas.clear();
+        self.redraw_popup(cx);
+    }
+    fn redraw_popup(&mut self, cx: &mut Cx) {
+        if let Some(popup) = self.popup.as_ref() {
+            let global = cx.global::<PopupMenuGlobal>().clone();
+            let mut map = global.map.borrow_mut();
+            if let Some(popup) = map.get_mut(popup) {
+                popup.redraw(cx);
+            }
+        }
+        self.input.redraw(cx);
+    }
+    pub fn set_provider<F>(&mut self, provider: F)
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.provider.replace(Rc::new(provider));
+    }
+    pub fn clear_provider(&mut self) {
+        self.provider = None;
+    }
+    pub fn set_suggestions(&mut self, cx: &mut Cx, suggestions: Vec<String>) {
+        self.suggestions = suggestions;
+        if self.opened {
+            let text = self.input.text.to_string();
+            self.update_matches(cx, &text);
+        }
+    }
+}
+
+impl GAutoCompleteRef {
+    ref_event_option! {
+        changed => GInputChangedParam,
+        selected => GAutoCompleteSelectedParam
+    }
+    pub fn set_provider<F>(&self, provider: F)
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_provider(provider);
+        }
+    }
+    pub fn clear_provider(&self) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.clear_provider();
+        }
+    }
+    pub fn set_suggestions(&self, cx: &mut Cx, suggestions: Vec<String>) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_suggestions(cx, suggestions);
+        }
+    }
+    pub fn get_suggestions(&self) -> Vec<String> {
+        self.borrow()
+            .map_or(Vec::new(), |c_ref| c_ref.suggestions.clone())
+    }
+    pub fn open(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.open(cx);
+        }
+    }
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.close(cx);
+        }
+    }
+    pub fn set_key_focus(&self, cx: &mut Cx) {
+        if let Some(c_ref) = self.borrow() {
+            c_ref.input.set_key_focus(cx);
+        }
+    }
+}
+
+impl GAutoCompleteSet {
+    set_event! {
+        changed => GInputChangedParam,
+        selected => GAutoCompleteSelectedParam
+    }
+}
@@ -23,3 +23,52 @@ pub enum InputEventType {
     Input,
     Cut,
 }
+
+// -------------------------------------------------------------------------
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GAutoCompleteEvent {
+    /// text of the inner input changed
+    Changed(GInputChangedParam),
+    /// a suggestion is selected (by click or Enter)
+    Selected(GAutoCompleteSelectedParam),
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct GAutoCompleteSelectedParam {
+    /// the selected suggestion text
+    pub text: String,
+    /// index of the selected item in the suggestion list
+    pub index: usize,
+}
+
+// -------------------------------------------------------------------------
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GNumberInputEvent {
+    /// the value is changed (by stepping or committing the typed text)
+    Changed(GNumberInputChangedParam),
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct GNumberInputChangedParam {
+    /// the clamped value
+    pub value: f64,
+    /// the formatted text
+    pub text: String,
+    pub ty: NumberInputEventType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberInputEventType {
+    /// stepper buttons
+    Stepper,
+    /// mouse wheel
+    Wheel,
+    /// arrow keys
+    KeyDown(KeyCode),
+    /// typed text is committed (Enter or key focus lost)
+    Input,
+}
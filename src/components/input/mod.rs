@@ -1,11 +1,16 @@
+mod auto_complete;
 mod event;
+mod number;
 pub mod register;
 mod types;
+pub use auto_complete::*;
 pub use event::*;
+pub use number::*;
+pub use types::{clamp_number, commit_number, fuzzy_filter, fuzzy_match, FuzzyMatch, NumberLocale};
 use makepad_widgets::*;
 
 use shader::draw_text::TextWrap;
-use types::{Edit, EditKind, History};
+use types::{Composition, Edit, EditKind, History};
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
 use crate::{
@@ -113,6 +118,12 @@ live_design! {
             }
         }
 
+        draw_composition: {
+            fn pixel(self) -> vec4 {
+                return self.background_color;
+            }
+        }
+
         // draw_selection: {
         //     instance hover: 0.0
         //     instance focus: 0.0
@@ -137,6 +148,30 @@ live_design! {
     }
 }
 
+/// # GInput
+/// A text input component, supports single and multi line editing, undo/redo and IME.
+///
+/// ## Keymap
+/// `Word` is `Alt` on macOS and `Ctrl` on other platforms, hold `Shift` to extend the selection.
+/// |key                       |action                                   |
+/// |--------------------------|-----------------------------------------|
+/// |`Left`/`Right`            |move by grapheme                         |
+/// |`Word`+`Left`/`Right`     |move by word                             |
+/// |`Cmd`+`Left`/`Right`      |move to line start/end (macOS)           |
+/// |`Home`/`End`              |move to line start/end                   |
+/// |`Ctrl`+`Home`/`End`       |move to text start/end                   |
+/// |`Up`/`Down`               |move by line                             |
+/// |`Backspace`/`Delete`      |delete grapheme                          |
+/// |`Word`+`Backspace`/`Delete`|delete word                             |
+/// |`Cmd`+`Backspace`/`Delete`|delete to line start/end (macOS)         |
+/// |`Ctrl`/`Cmd`+`A`          |select all                               |
+/// |`Ctrl`/`Cmd`+`Z`          |undo, with `Shift` to redo               |
+/// |double/triple click       |select word/line (four clicks select all)|
+///
+/// ## IME
+/// The composition (preedit) text is drawn inline with an underline, the cursor stays at the end of the preedit.
+/// Editing keys are left to the IME while composing, the composition is committed (one undo step, one `Changed` event)
+/// when the IME confirms it, `Enter` is pressed, the input is clicked or loses key focus.
 #[derive(Live, Widget)]
 pub struct GInput {
     #[live]
@@ -216,6 +251,13 @@ pub struct GInput {
     draw_selection: DrawGView,
     #[live]
     draw_cursor: DrawGView,
+    #[live]
+    draw_composition: DrawGView,
+    /// underline color of the IME composition text
+    #[live]
+    pub composition_color: Option<Vec4>,
+    #[live(1.5)]
+    pub composition_underline_width: f64,
     #[layout]
     layout: Layout,
     #[walk]
@@ -234,6 +276,22 @@ pub struct GInput {
     cursor: Cursor,
     #[rust]
     history: History,
+    #[rust]
+    composition: Option<Composition>,
+    /// range of the last plain text input, the platform sends the first char of a composition as a plain input,
+    /// the composition replaces it when the first `replace_last` input arrives
+    #[rust]
+    last_input: Option<Composition>,
+    /// max count of undo steps, `0` means unbounded
+    #[live(100)]
+    pub max_history: usize,
+    /// edits of the same kind happen within this window (seconds) are merged into one undo step,
+    /// `0.0` means no time limit
+    #[live(0.0)]
+    pub history_merge_window: f64,
+    /// record `set_text` as an undoable edit instead of clearing the history
+    #[live]
+    pub record_set_text: bool,
     #[live]
     scroll_bars: ScrollBars,
     #[live(true)]
@@ -285,6 +343,38 @@ impl Widget for GInput {
             );
         }
 
+        // Draw composition underline
+        if let Some(composition) = self.composition.filter(|c| !c.is_empty()) {
+            let rects = self.draw_text.selected_rects(
+                cx,
+                inner_walk,
+                self.text_align,
+                padded_rect.size.x,
+                &self.text,
+                IndexAffinity {
+                    index: composition.start,
+                    affinity: Affinity::After,
+                },
+                IndexAffinity {
+                    index: composition.end,
+                    affinity: Affinity::Before,
+                },
+            );
+            for rect in rects {
+                self.draw_composition.draw_abs(
+                    cx,
+                    Rect {
+                        pos: padded_rect.pos
+                            + dvec2(
+                                rect.pos.x,
+                                rect.pos.y + rect.size.y - self.composition_underline_width,
+                            ),
+                        size: dvec2(rect.size.x, self.composition_underline_width),
+                    },
+                );
+            }
+        }
+
         // Draw cursor
         let cursor_position = self.cursor_position(cx, padded_rect.size.x);
         let cursor_height = self.draw_text.line_height(cx);
@@ -331,7 +421,43 @@ impl Widget for GInput {
             self.draw_input.redraw(cx);
         }
 
-        match event.hits(cx, self.draw_input.area()) {
+        let hit = event.hits(cx, self.draw_input.area());
+        if self.composition.is_some() {
+            match &hit {
+                // the IME handles the editing keys while composing
+                Hit::KeyDown(KeyEvent {
+                    key_code:
+                        KeyCode::Backspace
+                        | KeyCode::Delete
+                        | KeyCode::Escape
+                        | KeyCode::ArrowLeft
+                        | KeyCode::ArrowRight
+                        | KeyCode::ArrowUp
+                        | KeyCode::ArrowDown
+                        | KeyCode::Home
+                        | KeyCode::End,
+                    ..
+                }) => return,
+                Hit::KeyDown(KeyEvent {
+                    key_code: KeyCode::ReturnKey,
+                    ..
+                }) => {
+                    self.end_composition(cx, uid, &scope.path);
+                    return;
+                }
+                Hit::KeyDown(KeyEvent { modifiers, .. })
+                    if modifiers.control || modifiers.logo =>
+                {
+                    self.end_composition(cx, uid, &scope.path);
+                }
+                Hit::FingerDown(_) | Hit::KeyFocusLost(_) => {
+                    self.end_composition(cx, uid, &scope.path);
+                }
+                _ => {}
+            }
+        }
+
+        match hit {
             Hit::KeyFocus(e) => {
                 self.animator_play(cx, id!(focus.on));
                 self.force_new_edit_group();
@@ -345,22 +471,30 @@ impl Widget for GInput {
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::ArrowLeft,
-                modifiers: KeyModifiers {
-                    shift: is_select, ..
-                },
+                modifiers,
                 ..
             }) => {
-                self.move_cursor_left(is_select);
+                if is_line_modifier(&modifiers) {
+                    self.move_cursor_to_line_start(modifiers.shift);
+                } else if is_word_modifier(&modifiers) {
+                    self.move_cursor_word_left(modifiers.shift);
+                } else {
+                    self.move_cursor_left(modifiers.shift);
+                }
                 self.draw_input.redraw(cx);
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::ArrowRight,
-                modifiers: KeyModifiers {
-                    shift: is_select, ..
-                },
+                modifiers,
                 ..
             }) => {
-                self.move_cursor_right(is_select);
+                if is_line_modifier(&modifiers) {
+                    self.move_cursor_to_line_end(modifiers.shift);
+                } else if is_word_modifier(&modifiers) {
+                    self.move_cursor_word_right(modifiers.shift);
+                } else {
+                    self.move_cursor_right(modifiers.shift);
+                }
                 self.draw_input.redraw(cx);
             }
             Hit::KeyDown(KeyEvent {
@@ -389,29 +523,39 @@ impl Widget for GInput {
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::Home,
+                modifiers,
                 ..
             }) => {
-                self.move_cursor_to(
-                    IndexAffinity {
-                        index: 0,
-                        affinity: Affinity::Before,
-                    },
-                    false,
-                );
+                if modifiers.control || modifiers.logo {
+                    self.move_cursor_to(
+                        IndexAffinity {
+                            index: 0,
+                            affinity: Affinity::Before,
+                        },
+                        modifiers.shift,
+                    );
+                } else {
+                    self.move_cursor_to_line_start(modifiers.shift);
+                }
                 self.history.force_new_edit_group();
                 self.draw_input.redraw(cx);
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::End,
+                modifiers,
                 ..
             }) => {
-                self.move_cursor_to(
-                    IndexAffinity {
-                        index: self.text.len(),
-                        affinity: Affinity::After,
-                    },
-                    false,
-                );
+                if modifiers.control || modifiers.logo {
+                    self.move_cursor_to(
+                        IndexAffinity {
+                            index: self.text.len(),
+                            affinity: Affinity::After,
+                        },
+                        modifiers.shift,
+                    );
+                } else {
+                    self.move_cursor_to_line_end(modifiers.shift);
+                }
                 self.history.force_new_edit_group();
                 self.draw_input.redraw(cx);
             }
@@ -439,8 +583,11 @@ impl Widget for GInput {
                 modifiers: KeyModifiers { shift: true, .. },
                 ..
             }) if !self.read_only => {
-                self.history
-                    .create_or_extend_edit_group(EditKind::Other, self.cursor);
+                self.history.create_or_extend_edit_group(
+                    EditKind::Other,
+                    self.cursor,
+                    cx.seconds_since_app_start(),
+                );
                 self.apply_edit(Edit {
                     start: self.cursor.start().index,
                     end: self.cursor.end().index,
@@ -479,15 +626,29 @@ impl Widget for GInput {
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::Backspace,
+                modifiers,
                 ..
             }) if !self.read_only => {
                 let mut start = self.cursor.start().index;
                 let end = self.cursor.end().index;
                 if start == end {
-                    start = prev_grapheme_boundary(&self.text, start).unwrap_or(0);
+                    start = if is_line_modifier(&modifiers) {
+                        self.line_start(start)
+                    } else if is_word_modifier(&modifiers) {
+                        self.prev_word_index(start)
+                    } else {
+                        prev_grapheme_boundary(&self.text, start).unwrap_or(0)
+                    };
                 }
-                self.history
-                    .create_or_extend_edit_group(EditKind::Backspace, self.cursor);
+                self.history.create_or_extend_edit_group(
+                    if modifiers.control || modifiers.alt || modifiers.logo {
+                        EditKind::Other
+                    } else {
+                        EditKind::Backspace
+                    },
+                    self.cursor,
+                    cx.seconds_since_app_start(),
+                );
                 self.apply_edit(Edit {
                     start,
                     end,
@@ -506,15 +667,29 @@ impl Widget for GInput {
             }
             Hit::KeyDown(KeyEvent {
                 key_code: KeyCode::Delete,
+                modifiers,
                 ..
             }) if !self.read_only => {
                 let start = self.cursor.start().index;
                 let mut end = self.cursor.end().index;
                 if start == end {
-                    end = next_grapheme_boundary(&self.text, end).unwrap_or(self.text.len());
+                    end = if is_line_modifier(&modifiers) {
+                        self.line_end(end)
+                    } else if is_word_modifier(&modifiers) {
+                        self.next_word_index(end)
+                    } else {
+                        next_grapheme_boundary(&self.text, end).unwrap_or(self.text.len())
+                    };
                 }
-                self.history
-                    .create_or_extend_edit_group(EditKind::Delete, self.cursor);
+                self.history.create_or_extend_edit_group(
+                    if modifiers.control || modifiers.alt || modifiers.logo {
+                        EditKind::Other
+                    } else {
+                        EditKind::Delete
+                    },
+                    self.cursor,
+                    cx.seconds_since_app_start(),
+                );
                 self.apply_edit(Edit {
                     start,
                     end,
@@ -588,28 +763,36 @@ impl Widget for GInput {
                 ..
             }) if !self.read_only => {
                 let input = self.filter_input(input);
+                if replace_last && !was_paste {
+                    // IME composition update, `Changed` is sent when the composition ends
+                    if self.update_composition(input, cx.seconds_since_app_start()) {
+                        self.draw_input.redraw(cx);
+                    }
+                    return;
+                }
+                self.end_composition(cx, uid, &scope.path);
                 if !input.is_empty() {
-                    let mut start = self.cursor.start().index;
+                    let start = self.cursor.start().index;
                     let end = self.cursor.end().index;
-                    if replace_last {
-                        start -= self
-                            .history
-                            .last_inserted_text(&self.text)
-                            .map_or(0, |text| text.len());
-                    }
                     self.history.create_or_extend_edit_group(
-                        if replace_last || was_paste {
+                        if was_paste {
                             EditKind::Other
                         } else {
                             EditKind::Insert
                         },
                         self.cursor,
+                        cx.seconds_since_app_start(),
                     );
+                    let input_end = start + input.len();
                     self.apply_edit(Edit {
                         start,
                         end,
                         replace_with: input,
                     });
+                    self.last_input.replace(Composition {
+                        start,
+                        end: input_end,
+                    });
                     self.draw_input.redraw(cx);
                     cx.widget_action(
                         uid,
@@ -630,8 +813,11 @@ impl Widget for GInput {
                 let selection = &self.text[self.cursor.start().index..self.cursor.end().index];
                 *event.response.borrow_mut() = Some(selection.to_string());
                 if !selection.is_empty() {
-                    self.history
-                        .create_or_extend_edit_group(EditKind::Other, self.cursor);
+                    self.history.create_or_extend_edit_group(
+                        EditKind::Other,
+                        self.cursor,
+                        cx.seconds_since_app_start(),
+                    );
                     self.apply_edit(Edit {
                         start: self.cursor.start().index,
                         end: self.cursor.end().index,
@@ -668,6 +854,8 @@ impl Widget for GInput {
                 if tap_count == 2 {
                     self.select_word();
                 } else if tap_count == 3 {
+                    self.select_line();
+                } else if tap_count >= 4 {
                     self.select_all();
                 }
                 self.set_key_focus(&mut *cx);
@@ -685,6 +873,8 @@ impl Widget for GInput {
                 if tap_count == 2 {
                     self.select_word();
                 } else if tap_count == 3 {
+                    self.select_line();
+                } else if tap_count >= 4 {
                     self.select_all();
                 }
                 self.draw_input.redraw(&mut *cx);
@@ -700,10 +890,16 @@ impl Widget for GInput {
         if self.text == text {
             return;
         }
-        self.text = self.filter_input(text.to_string());
-        self.cursor.head.index = self.cursor.head.index.min(text.len());
-        self.cursor.tail.index = self.cursor.tail.index.min(text.len());
-        self.history.clear();
+        self.composition = None;
+        self.last_input = None;
+        if self.record_set_text {
+            self.set_text_undoable(cx, text);
+        } else {
+            self.text = self.filter_input(text.to_string());
+            self.cursor.head.index = self.cursor.head.index.min(self.text.len());
+            self.cursor.tail.index = self.cursor.tail.index.min(self.text.len());
+            self.history.clear();
+        }
         self.redraw(cx);
     }
 
@@ -749,6 +945,9 @@ impl GInput {
         let font_color = self.color.get(self.theme, 800);
         // ---------------------- is empty ------------------------------------------------
         let empty = self.text.len().eq(&0).to_f32();
+        // ---------------------- history -------------------------------------------------
+        self.history.set_max_depth(self.max_history);
+        self.history.set_group_window(self.history_merge_window);
         // draw input --------------------------------------------------------------
         self.draw_input.apply_over(
             cx,
@@ -798,6 +997,14 @@ impl GInput {
                 hover_color: (cursor_hover_color),
             },
         );
+        // draw composition --------------------------------------------------------
+        let composition_color = self.composition_color.get(self.theme, 500);
+        self.draw_composition.apply_over(
+            cx,
+            live! {
+                background_color: (composition_color),
+            },
+        );
         // draw select -------------------------------------------------------------
         self.draw_selection.apply_over(
             cx,
@@ -1045,6 +1252,50 @@ impl GInput {
         );
     }
 
+    fn move_cursor_word_left(&mut self, is_select: bool) {
+        let index = self.prev_word_index(self.cursor.head.index);
+        self.move_cursor_to(
+            IndexAffinity {
+                index,
+                affinity: Affinity::After,
+            },
+            is_select,
+        );
+    }
+
+    fn move_cursor_word_right(&mut self, is_select: bool) {
+        let index = self.next_word_index(self.cursor.head.index);
+        self.move_cursor_to(
+            IndexAffinity {
+                index,
+                affinity: Affinity::Before,
+            },
+            is_select,
+        );
+    }
+
+    fn move_cursor_to_line_start(&mut self, is_select: bool) {
+        let index = self.line_start(self.cursor.head.index);
+        self.move_cursor_to(
+            IndexAffinity {
+                index,
+                affinity: Affinity::Before,
+            },
+            is_select,
+        );
+    }
+
+    fn move_cursor_to_line_end(&mut self, is_select: bool) {
+        let index = self.line_end(self.cursor.head.index);
+        self.move_cursor_to(
+            IndexAffinity {
+                index,
+                affinity: Affinity::After,
+            },
+            is_select,
+        );
+    }
+
     fn move_cursor_up(&mut self, cx: &mut Cx2d, width: f64, is_select: bool) {
         let position = self.cursor_position(cx, width);
         let line_spacing = self.draw_text.line_spacing(cx);
@@ -1104,6 +1355,59 @@ impl GInput {
         }
     }
 
+    /// select the whole line (split by `\n`) where the cursor is
+    fn select_line(&mut self) {
+        let index = self.cursor.head.index;
+        self.cursor.tail = IndexAffinity {
+            index: self.line_start(index),
+            affinity: Affinity::Before,
+        };
+        self.cursor.head = IndexAffinity {
+            index: self.line_end(index),
+            affinity: Affinity::After,
+        };
+    }
+
+    /// the start index of the word before the index, whitespace between is skipped
+    fn prev_word_index(&self, index: usize) -> usize {
+        let mut index = index;
+        while let Some(prev) = prev_grapheme_boundary(&self.text, index) {
+            if !self.text[prev..index].chars().all(char::is_whitespace) {
+                break;
+            }
+            index = prev;
+        }
+        if index == 0 {
+            return 0;
+        }
+        self.ceil_word_boundary(index - 1)
+    }
+
+    /// the end index of the word after the index, whitespace between is skipped
+    fn next_word_index(&self, index: usize) -> usize {
+        let mut index = index;
+        while let Some(next) = next_grapheme_boundary(&self.text, index) {
+            if !self.text[index..next].chars().all(char::is_whitespace) {
+                break;
+            }
+            index = next;
+        }
+        if index >= self.text.len() {
+            return self.text.len();
+        }
+        self.floor_word_boundary(index + 1)
+    }
+
+    fn line_start(&self, index: usize) -> usize {
+        self.text[..index].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, index: usize) -> usize {
+        self.text[index..]
+            .find('\n')
+            .map_or(self.text.len(), |i| index + i)
+    }
+
     fn ceil_word_boundary(&self, index: usize) -> usize {
         let mut prev_word_boundary_index = 0;
         for (word_boundary_index, _) in self.text.split_word_bound_indices() {
@@ -1127,20 +1431,131 @@ impl GInput {
     }
 
     fn apply_edit(&mut self, edit: Edit) {
+        self.last_input = None;
         self.cursor.head.index = edit.start + edit.replace_with.len();
         self.cursor.tail = self.cursor.head;
         self.history.apply_edit(edit, &mut self.text);
     }
 
-    fn undo(&mut self) {
+    /// undo the last edit group, back `false` if nothing can be undone
+    pub fn undo(&mut self) -> bool {
+        self.commit_composition();
+        self.last_input = None;
         if let Some(cursor) = self.history.undo(self.cursor, &mut self.text) {
             self.cursor = cursor;
+            true
+        } else {
+            false
         }
     }
-
-    fn redo(&mut self) {
+    /// redo the last undone edit group, back `false` if nothing can be redone
+    pub fn redo(&mut self) -> bool {
+        self.commit_composition();
+        self.last_input = None;
         if let Some(cursor) = self.history.redo(self.cursor, &mut self.text) {
             self.cursor = cursor;
+            true
+        } else {
+            false
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+    /// replace the whole text as one undoable edit (the cursor is moved to the end)
+    pub fn set_text_undoable(&mut self, cx: &mut Cx, text: &str) {
+        let text = self.filter_input(text.to_string());
+        if self.text == text {
+            return;
+        }
+        self.history.force_new_edit_group();
+        self.history.create_or_extend_edit_group(
+            EditKind::Other,
+            self.cursor,
+            cx.seconds_since_app_start(),
+        );
+        self.apply_edit(Edit {
+            start: 0,
+            end: self.text.len(),
+            replace_with: text,
+        });
+        self.history.force_new_edit_group();
+    }
+    /// is the IME composition (preedit) in progress
+    pub fn is_composing(&self) -> bool {
+        self.composition.is_some()
+    }
+    /// ## replace the preedit text with the new composition text
+    /// if no composition is in progress, the composition starts from the last plain input
+    /// (the platform sends the first char of the composition as a plain input) if the cursor is still at its end,
+    /// otherwise from the cursor.
+    /// back `true` if the text is changed
+    fn update_composition(&mut self, input: String, time: f64) -> bool {
+        let (start, end) = match self.composition {
+            Some(composition) => (composition.start, composition.end),
+            None => {
+                let cursor = self.cursor;
+                let anchor = self.last_input.take().filter(|last| {
+                    cursor.head.index == cursor.tail.index
+                        && cursor.head.index == last.end
+                        && last.end <= self.text.len()
+                });
+                // the platform confirms the composition with the same text
+                if input.is_empty()
+                    || anchor.is_some_and(|last| self.text[last.start..last.end] == input)
+                {
+                    return false;
+                }
+                anchor.map_or((cursor.start().index, cursor.end().index), |last| {
+                    (last.start, last.end)
+                })
+            }
+        };
+        self.history
+            .create_or_extend_edit_group(EditKind::Composition, self.cursor, time);
+        let end_index = start + input.len();
+        self.apply_edit(Edit {
+            start,
+            end,
+            replace_with: input,
+        });
+        // empty input means the composition is cancelled
+        self.composition = (end_index > start).then_some(Composition {
+            start,
+            end: end_index,
+        });
+        if self.composition.is_none() {
+            self.history.force_new_edit_group();
+        }
+        true
+    }
+    /// commit the composition, the next edit will start a new undo group. back `true` if a composition is committed
+    fn commit_composition(&mut self) -> bool {
+        if self.composition.take().is_some() {
+            self.history.force_new_edit_group();
+            true
+        } else {
+            false
+        }
+    }
+    fn end_composition(&mut self, cx: &mut Cx, uid: WidgetUid, path: &HeapLiveIdPath) {
+        if self.commit_composition() {
+            self.draw_input.redraw(cx);
+            cx.widget_action(
+                uid,
+                path,
+                GInputEvent::Changed(GInputChangedParam {
+                    text: self.text.clone(),
+                    ty: InputEventType::Input,
+                    modifiers: None,
+                }),
+            );
         }
     }
     setter! {
@@ -1181,6 +1596,9 @@ impl GInput {
             set_text(text: String) {|c, _cx| {c.text = text; Ok(())}},
             set_cursor(cursor: Cursor) {|c, _cx| {c.cursor = cursor; Ok(())}},
             set_event_key(event_key: bool) {|c, _cx| {c.event_key = event_key; Ok(())}},
+            set_max_history(max_history: usize) {|c, _cx| {c.max_history = max_history; c.history.set_max_depth(max_history); Ok(())}},
+            set_history_merge_window(window: f64) {|c, _cx| {c.history_merge_window = window; c.history.set_group_window(window); Ok(())}},
+            set_record_set_text(record: bool) {|c, _cx| {c.record_set_text = record; Ok(())}},
             set_abs_pos(abs_pos: Option<DVec2>) {|c, _cx| {c.walk.abs_pos = abs_pos; Ok(())}},
             set_margin(margin: Margin) {|c, _cx| {c.walk.margin = margin; Ok(())}},
             set_height(height: Size) {|c, _cx| {c.walk.height = height; Ok(())}},
@@ -1232,6 +1650,9 @@ impl GInput {
             get_text(String) {|c| {c.text.to_string()}},
             get_cursor(Cursor) {|c| {c.cursor}},
             get_event_key(bool) {|c| {c.event_key}},
+            get_max_history(usize) {|c| {c.max_history}},
+            get_history_merge_window(f64) {|c| {c.history_merge_window}},
+            get_record_set_text(bool) {|c| {c.record_set_text}},
             get_abs_pos(Option<DVec2>) {|c| {c.walk.abs_pos}},
             get_margin(Margin) {|c| {c.walk.margin}},
             get_height(Size) {|c| {c.walk.height}},
@@ -1285,6 +1706,9 @@ impl GInputRef {
         get_text, set_text -> String,
         get_cursor, set_cursor -> Cursor,
         get_event_key, set_event_key -> bool,
+        get_max_history, set_max_history -> usize,
+        get_history_merge_window, set_history_merge_window -> f64,
+        get_record_set_text, set_record_set_text -> bool,
         get_abs_pos, set_abs_pos -> Option<DVec2>,
         get_margin, set_margin -> Margin,
         get_height, set_height -> Size,
@@ -1344,6 +1768,44 @@ impl GInputRef {
             inner.set_key_focus(cx);
         }
     }
+    /// undo the last edit group and redraw, back `false` if nothing can be undone
+    pub fn undo(&self, cx: &mut Cx) -> bool {
+        self.borrow_mut().map_or(false, |mut inner| {
+            let undone = inner.undo();
+            if undone {
+                inner.redraw(cx);
+            }
+            undone
+        })
+    }
+    /// redo the last undone edit group and redraw, back `false` if nothing can be redone
+    pub fn redo(&self, cx: &mut Cx) -> bool {
+        self.borrow_mut().map_or(false, |mut inner| {
+            let redone = inner.redo();
+            if redone {
+                inner.redraw(cx);
+            }
+            redone
+        })
+    }
+    pub fn can_undo(&self) -> bool {
+        self.borrow().map_or(false, |inner| inner.can_undo())
+    }
+    pub fn can_redo(&self) -> bool {
+        self.borrow().map_or(false, |inner| inner.can_redo())
+    }
+    pub fn clear_history(&self) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear_history();
+        }
+    }
+    /// set text as an undoable edit, it can be reverted by `undo`
+    pub fn set_text_undoable(&self, cx: &mut Cx, text: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_text_undoable(cx, text);
+            inner.redraw(cx);
+        }
+    }
 }
 
 impl GInputSet {
@@ -1365,3 +1827,17 @@ fn prev_grapheme_boundary(string: &str, index: usize) -> Option<usize> {
     let mut cursor = GraphemeCursor::new(index, string.len(), true);
     cursor.prev_boundary(string, 0).unwrap()
 }
+
+/// modifier for word jump and word deletion: `Alt` on macOS, `Ctrl` on other platforms
+fn is_word_modifier(modifiers: &KeyModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.alt
+    } else {
+        modifiers.control
+    }
+}
+
+/// modifier for line jump and line deletion, only `Cmd` on macOS (other platforms use Home/End)
+fn is_line_modifier(modifiers: &KeyModifiers) -> bool {
+    cfg!(target_os = "macos") && modifiers.logo
+}
@@ -0,0 +1,503 @@
+use makepad_widgets::*;
+
+use crate::{
+    event_option, getter, pure_after_apply, ref_event_option, ref_getter_setter,
+    render_after_apply, set_event, setter,
+    shader::draw_view::DrawGView,
+    themes::Themes,
+    utils::{BoolToF32, ThemeColor},
+};
+
+use super::{
+    types::{clamp_number, commit_number, NumberLocale},
+    GInput, GNumberInputChangedParam, GNumberInputEvent, InputEventType, NumberInputEventType,
+};
+
+live_design! {
+    link gen_base;
+    use link::shaders::*;
+    use link::gen_theme::*;
+
+    pub GNumberInputBase = {{GNumberInput}}{
+        height: 36.0,
+        width: 180.0,
+        flow: Right,
+        spacing: 4.0,
+        stepper_width: 24.0,
+        locale: "en-US",
+        draw_stepper: {
+            instance stroke_color: vec4,
+            instance stroke_width: 1.2,
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    0.0,
+                    0.0,
+                    self.rect_size.x,
+                    self.rect_size.y,
+                    self.border_radius
+                );
+                sdf.fill(self.get_background_color());
+                // rotation 0.0 => up, PI => down
+                let dir = cos(self.rotation);
+                let c = self.rect_size * 0.5;
+                let size = min(self.rect_size.x, self.rect_size.y) * 0.24;
+                sdf.move_to(c.x - size, c.y + size * 0.5 * dir);
+                sdf.line_to(c.x, c.y - size * 0.5 * dir);
+                sdf.line_to(c.x + size, c.y + size * 0.5 * dir);
+                sdf.stroke(self.stroke_color, self.stroke_width);
+                return sdf.result;
+            }
+        }
+    }
+}
+
+/// # GNumberInput
+/// A `GInput` for numbers with up/down stepper buttons.
+///
+/// - stepper buttons (press and hold to repeat), mouse wheel and `Up`/`Down` keys change the value by `step`
+/// - `PageUp`/`PageDown` or `Shift` + `Up`/`Down` change the value by `step * 10`
+/// - the value is always clamped into `[min, max]`, snapped to `step` and rounded to `precision` decimals
+/// - the text is formatted by `locale` (such as `en-US`, `de-DE`), when the input is focused the group separator is hidden
+/// - typed text is committed when `Enter` is pressed or the input loses key focus
+///
+/// ## Example
+/// ```rust
+/// let num = self.ui.gnumber_input(id!(num));
+/// if let Some(e) = num.changed(&actions) {
+///     log!("value: {}", e.value);
+/// }
+/// ```
+#[derive(Live, Widget)]
+pub struct GNumberInput {
+    #[live]
+    pub theme: Themes,
+    #[live]
+    pub input: GInput,
+    #[live(f64::NEG_INFINITY)]
+    pub min: f64,
+    #[live(f64::INFINITY)]
+    pub max: f64,
+    #[live(1.0)]
+    pub step: f64,
+    #[live(0)]
+    pub precision: usize,
+    #[live(0.0)]
+    pub value: f64,
+    /// locale tag used to format the value, such as `en-US`, `de-DE`
+    #[live]
+    pub locale: String,
+    /// use group (thousands) separator when the input is not focused
+    #[live(true)]
+    pub grouping: bool,
+    #[live(true)]
+    pub stepper_visible: bool,
+    #[live(24.0)]
+    pub stepper_width: f64,
+    #[live(true)]
+    pub wheel_step: bool,
+    /// delay (seconds) before a pressed stepper starts repeating
+    #[live(0.4)]
+    pub repeat_delay: f64,
+    /// interval (seconds) of repeating when a stepper is held
+    #[live(0.06)]
+    pub repeat_interval: f64,
+    #[live]
+    pub stepper_color: Option<Vec4>,
+    #[live]
+    pub stepper_background_color: Option<Vec4>,
+    #[live]
+    pub stepper_hover_color: Option<Vec4>,
+    #[live]
+    pub stepper_pressed_color: Option<Vec4>,
+    #[live(2.0)]
+    pub stepper_border_radius: f32,
+    #[redraw]
+    #[live]
+    pub draw_stepper: DrawGView,
+    #[walk]
+    pub walk: Walk,
+    #[layout]
+    pub layout: Layout,
+    #[live(true)]
+    pub visible: bool,
+    #[live(true)]
+    pub event_key: bool,
+    #[rust]
+    stepper_areas: [Area; 2],
+    /// hovered stepper, `true` is up
+    #[rust]
+    hover_stepper: Option<bool>,
+    /// pressed stepper, `true` is up
+    #[rust]
+    pressed_stepper: Option<bool>,
+    #[rust]
+    repeat_timer: Timer,
+}
+
+impl LiveHook for GNumberInput {
+    fn after_apply(&mut self, _cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if !apply.from.is_from_doc() {
+            return;
+        }
+        self.value = self.clamp(self.value);
+        let text = self.format_value(false);
+        self.input.text = text;
+    }
+    pure_after_apply!();
+}
+
+impl Widget for GNumberInput {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        if !self.visible {
+            return DrawStep::done();
+        }
+        cx.begin_turtle(walk, self.layout);
+        let _ = self.input.draw_walk(
+            cx,
+            scope,
+            Walk {
+                width: Size::Fill,
+                height: Size::Fill,
+                ..Default::default()
+            },
+        );
+        if self.stepper_visible {
+            cx.begin_turtle(
+                Walk {
+                    width: Size::Fixed(self.stepper_width),
+                    height: Size::Fill,
+                    ..Default::default()
+                },
+                Layout {
+                    flow: Flow::Down,
+                    ..Default::default()
+                },
+            );
+            for (index, up) in [true, false].into_iter().enumerate() {
+                self.draw_stepper.rotation = if up { 0.0 } else { std::f32::consts::PI };
+                self.draw_stepper.hover = (self.hover_stepper == Some(up)).to_f32();
+                self.draw_stepper.focus = (self.pressed_stepper == Some(up)).to_f32();
+                self.draw_stepper.draw_walk(cx, Walk::fill());
+                self.stepper_areas[index] = self.draw_stepper.area();
+            }
+            cx.end_turtle();
+        }
+        cx.end_turtle();
+        DrawStep::done()
+    }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.visible {
+            return;
+        }
+        let uid = self.widget_uid();
+        let read_only = self.input.read_only;
+
+        if let Some(up) = self.pressed_stepper {
+            if self.repeat_timer.is_event(event).is_some() {
+                self.step_by(
+                    cx,
+                    uid,
+                    &scope.path,
+                    if up { 1.0 } else { -1.0 },
+                    NumberInputEventType::Stepper,
+                );
+                self.repeat_timer = cx.start_timeout(self.repeat_interval);
+            }
+        }
+
+        if self.stepper_visible && !read_only {
+            for (index, up) in [true, false].into_iter().enumerate() {
+                match event.hits(cx, self.stepper_areas[index]) {
+                    Hit::FingerHoverIn(_) => {
+                        cx.set_cursor(MouseCursor::Hand);
+                        self.hover_stepper = Some(up);
+                        self.redraw(cx);
+                    }
+                    Hit::FingerHoverOut(_) => {
+                        self.hover_stepper = None;
+                        self.redraw(cx);
+                    }
+                    Hit::FingerDown(_) => {
+                        self.pressed_stepper = Some(up);
+                        self.step_by(
+                            cx,
+                            uid,
+                            &scope.path,
+                            if up { 1.0 } else { -1.0 },
+                            NumberInputEventType::Stepper,
+                        );
+                        self.repeat_timer = cx.start_timeout(self.repeat_delay);
+                    }
+                    Hit::FingerUp(_) => {
+                        self.pressed_stepper = None;
+                        cx.stop_timer(self.repeat_timer);
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let focused = cx.has_key_focus(self.input.area());
+        if focused && !read_only {
+            if let Event::KeyDown(e) = event {
+                let factor = if e.modifiers.shift { 10.0 } else { 1.0 };
+                let steps = match e.key_code {
+                    KeyCode::ArrowUp => Some(factor),
+                    KeyCode::ArrowDown => Some(-factor),
+                    KeyCode::PageUp => Some(10.0),
+                    KeyCode::PageDown => Some(-10.0),
+                    _ => None,
+                };
+                if let Some(steps) = steps {
+                    self.commit_text();
+                    self.step_by(
+                        cx,
+                        uid,
+                        &scope.path,
+                        steps,
+                        NumberInputEventType::KeyDown(e.key_code),
+                    );
+                    return;
+                }
+            }
+        }
+
+        if self.wheel_step && focused && !read_only {
+            if let Hit::FingerScroll(e) = event.hits(cx, self.input.area()) {
+                if e.scroll.y != 0.0 {
+                    self.commit_text();
+                    let steps = if e.scroll.y < 0.0 { 1.0 } else { -1.0 };
+                    self.step_by(cx, uid, &scope.path, steps, NumberInputEventType::Wheel);
+                }
+            }
+        }
+
+        let actions = cx.capture_actions(|cx| self.input.handle_event(cx, event, scope));
+        if self.input.key_focus(&actions).is_some() {
+            // hide the group separators when editing
+            let text = self.format_value(false);
+            self.set_input_text(cx, text);
+        }
+        if let Some(param) = self.input.changed(&actions) {
+            if let InputEventType::KeyDown(KeyCode::ReturnKey) = param.ty {
+                self.commit(cx, uid, &scope.path, false);
+            } else {
+                let locale = self.number_locale();
+                let filtered = param
+                    .text
+                    .chars()
+                    .filter(|c| locale.is_number_char(*c))
+                    .collect::<String>();
+                if filtered != param.text {
+                    self.set_input_text(cx, filtered);
+                }
+            }
+        }
+        if self.input.key_focus_lost(&actions).is_some() {
+            self.commit(cx, uid, &scope.path, self.grouping);
+        }
+    }
+    fn text(&self) -> String {
+        self.input.text.to_string()
+    }
+    fn set_text(&mut self, cx: &mut Cx, v: &str) {
+        if let Some(value) = self.number_locale().parse(v) {
+            self.set_value(cx, value);
+        }
+    }
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl GNumberInput {
+    render_after_apply!("GNumberInput");
+    event_option! {
+        changed: GNumberInputEvent::Changed => GNumberInputChangedParam
+    }
+    pub fn render(&mut self, cx: &mut Cx) -> Result<(), Box<dyn std::error::Error>> {
+        let stepper_color = self.stepper_color.get(self.theme, 600);
+        let stepper_background_color = self.stepper_background_color.use_or("#FFFFFF00")?;
+        let stepper_hover_color = self.stepper_hover_color.get(self.theme, 50);
+        let stepper_pressed_color = self.stepper_pressed_color.get(self.theme, 100);
+        self.draw_stepper.apply_over(
+            cx,
+            live! {
+                background_color: (stepper_background_color),
+                hover_color: (stepper_hover_color),
+                focus_color: (stepper_pressed_color),
+                stroke_color: (stepper_color),
+                border_radius: (self.stepper_border_radius),
+            },
+        );
+        Ok(())
+    }
+    pub fn number_locale(&self) -> NumberLocale {
+        NumberLocale::from_tag(&self.locale)
+    }
+    pub fn clamp(&self, value: f64) -> f64 {
+        clamp_number(value, self.min, self.max, self.step, self.precision)
+    }
+    /// format the current value by the locale
+    pub fn format_value(&self, grouping: bool) -> String {
+        self.number_locale()
+            .format(self.value, self.precision, grouping)
+    }
+    /// set the value (clamped) and refresh the text, back the clamped value
+    pub fn set_value(&mut self, cx: &mut Cx, value: f64) -> f64 {
+        self.value = self.clamp(value);
+        let grouping = self.grouping && !cx.has_key_focus(self.input.area());
+        let text = self.format_value(grouping);
+        self.set_input_text(cx, text);
+        self.value
+    }
+    fn set_input_text(&mut self, cx: &mut Cx, text: String) {
+        let len = text.len();
+        // the widget `set_text` clears the history (or records the replace), the old edits can not be undone on the new text
+        Widget::set_text(&mut self.input, cx, &text);
+        self.input.set_input_cursor(Cursor {
+            head: IndexAffinity {
+                index: len,
+                affinity: Affinity::After,
+            },
+            tail: IndexAffinity {
+                index: len,
+                affinity: Affinity::After,
+            },
+        });
+        self.input.force_new_edit_group();
+        self.redraw(cx);
+        self.input.redraw(cx);
+    }
+    /// parse the typed text into the value without formatting the text
+    fn commit_text(&mut self) {
+        if let Some(value) = self.number_locale().parse(&self.input.text) {
+            self.value = self.clamp(value);
+        }
+    }
+    fn commit(&mut self, cx: &mut Cx, uid: WidgetUid, path: &HeapLiveIdPath, grouping: bool) {
+        let old = self.value;
+        let (value, text) = commit_number(
+            self.number_locale(),
+            &self.input.text,
+            old,
+            |v| self.clamp(v),
+            self.precision,
+            grouping,
+        );
+        self.value = value;
+        self.set_input_text(cx, text);
+        if old != self.value {
+            self.emit_changed(cx, uid, path, NumberInputEventType::Input);
+        }
+    }
+    fn step_by(
+        &mut self,
+        cx: &mut Cx,
+        uid: WidgetUid,
+        path: &HeapLiveIdPath,
+        steps: f64,
+        ty: NumberInputEventType,
+    ) {
+        let old = self.value;
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            10_f64.powi(-(self.precision.min(15) as i32))
+        };
+        self.set_value(cx, old + step * steps);
+        if old != self.value {
+            self.emit_changed(cx, uid, path, ty);
+        }
+    }
+    fn emit_changed(
+        &mut self,
+        cx: &mut Cx,
+        uid: WidgetUid,
+        path: &HeapLiveIdPath,
+        ty: NumberInputEventType,
+    ) {
+        if self.event_key {
+            cx.widget_action(
+                uid,
+                path,
+                GNumberInputEvent::Changed(GNumberInputChangedParam {
+                    value: self.value,
+                    text: self.input.text.to_string(),
+                    ty,
+                }),
+            );
+        }
+    }
+    setter! {
+        GNumberInput{
+            set_theme(theme: Themes) {|c, cx| {c.theme = theme; c.render(cx)}},
+            set_min(min: f64) {|c, cx| {c.min = min; let v = c.value; c.set_value(cx, v); Ok(())}},
+            set_max(max: f64) {|c, cx| {c.max = max; let v = c.value; c.set_value(cx, v); Ok(())}},
+            set_step(step: f64) {|c, _cx| {c.step = step; Ok(())}},
+            set_precision(precision: usize) {|c, cx| {c.precision = precision; let v = c.value; c.set_value(cx, v); Ok(())}},
+            set_locale(locale: String) {|c, cx| {c.locale = locale; let v = c.value; c.set_value(cx, v); Ok(())}},
+            set_grouping(grouping: bool) {|c, cx| {c.grouping = grouping; let v = c.value; c.set_value(cx, v); Ok(())}},
+            set_stepper_visible(visible: bool) {|c, _cx| {c.stepper_visible = visible; Ok(())}},
+            set_wheel_step(wheel_step: bool) {|c, _cx| {c.wheel_step = wheel_step; Ok(())}},
+            set_visible(visible: bool) {|c, _cx| {c.visible = visible; Ok(())}},
+            set_event_key(event_key: bool) {|c, _cx| {c.event_key = event_key; Ok(())}}
+        }
+    }
+    getter! {
+        GNumberInput{
+            get_theme(Themes) {|c| {c.theme}},
+            get_min(f64) {|c| {c.min}},
+            get_max(f64) {|c| {c.max}},
+            get_step(f64) {|c| {c.step}},
+            get_precision(usize) {|c| {c.precision}},
+            get_locale(String) {|c| {c.locale.to_string()}},
+            get_grouping(bool) {|c| {c.grouping}},
+            get_stepper_visible(bool) {|c| {c.stepper_visible}},
+            get_wheel_step(bool) {|c| {c.wheel_step}},
+            get_visible(bool) {|c| {c.visible}},
+            get_event_key(bool) {|c| {c.event_key}}
+        }
+    }
+}
+
+impl GNumberInputRef {
+    ref_getter_setter! {
+        get_theme, set_theme -> Themes,
+        get_min, set_min -> f64,
+        get_max, set_max -> f64,
+        get_step, set_step -> f64,
+        get_precision, set_precision -> usize,
+        get_locale, set_locale -> String,
+        get_grouping, set_grouping -> bool,
+        get_stepper_visible, set_stepper_visible -> bool,
+        get_wheel_step, set_wheel_step -> bool,
+        get_visible, set_visible -> bool,
+        get_event_key, set_event_key -> bool
+    }
+    ref_event_option! {
+        changed => GNumberInputChangedParam
+    }
+    /// get the current value, if the widget is not found, back `None`
+    pub fn value(&self) -> Option<f64> {
+        self.borrow().map(|c_ref| c_ref.value)
+    }
+    /// set the value, it will be clamped by `min`, `max`, `step` and `precision`
+    pub fn set_value(&self, cx: &mut Cx, value: f64) -> Option<f64> {
+        self.borrow_mut()
+            .map(|mut c_ref| c_ref.set_value(cx, value))
+    }
+    pub fn set_key_focus(&self, cx: &mut Cx) {
+        if let Some(c_ref) = self.borrow() {
+            c_ref.input.set_key_focus(cx);
+        }
+    }
+}
+
+impl GNumberInputSet {
+    set_event! {
+        changed => GNumberInputChangedParam
+    }
+}
@@ -2,4 +2,6 @@ use makepad_widgets::Cx;
 
 pub fn register(cx: &mut Cx) {
     crate::components::input::live_design(cx);
-}
\ No newline at end of file
+    crate::components::input::auto_complete::live_design(cx);
+    crate::components::input::number::live_design(cx);
+}
@@ -9,22 +9,44 @@ pub enum EditKind {
     Backspace,
     /// A character was deleted. figure up the backspace and delete keys
     Delete,
+    /// IME composition (preedit) update
+    Composition,
     /// other edit kind
     Other,
 }
 
 impl EditKind {
     pub fn can_merge_with(self, other: EditKind) -> bool {
-        if self == Self::Other {
-            false
-        } else {
-            self == other
+        match (self, other) {
+            (Self::Other, _) => false,
+            // the preedit replaces the char which starts the composition, keep them in one group
+            (Self::Insert, Self::Composition) => true,
+            _ => self == other,
         }
     }
 }
 
 // ------------------------------------------------------------------------------------------------------------
 
+/// An in-progress IME composition (preedit).
+/// The preedit text is already in the input text (`start..end`), but it is not committed,
+/// it will be replaced by the next composition update until the composition ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Composition {
+    /// byte index of the preedit start
+    pub start: usize,
+    /// byte index of the preedit end
+    pub end: usize,
+}
+
+impl Composition {
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+// ------------------------------------------------------------------------------------------------------------
+
 /// An edit that was made to the text.
 #[derive(Clone, Debug)]
 pub struct Edit {
@@ -93,14 +115,41 @@ impl EditStack {
         self.edit_groups.clear();
         self.edits.clear();
     }
+
+    fn is_empty(&self) -> bool {
+        self.edit_groups.is_empty()
+    }
+
+    /// drop the oldest edit groups till the stack has at most `max_depth` groups
+    fn trim(&mut self, max_depth: usize) {
+        while self.edit_groups.len() > max_depth {
+            self.edit_groups.remove(0);
+            let drain_end = self
+                .edit_groups
+                .first()
+                .map_or(self.edits.len(), |group| group.edit_start);
+            self.edits.drain(..drain_end);
+            for group in self.edit_groups.iter_mut() {
+                group.edit_start -= drain_end;
+            }
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------------------
+/// The undo/redo history of the input
+/// - `max_depth`: how many edit groups can be undone, `0` means unbounded
+/// - `group_window`: edits of the same kind are merged into one group only if they happen within this window (seconds),
+/// `0.0` means no time limit
 #[derive(Clone, Debug, Default)]
 pub struct History {
     current_edit_kind: Option<EditKind>,
     undo_stack: EditStack,
     redo_stack: EditStack,
+    max_depth: usize,
+    group_window: f64,
+    /// time (seconds since the app start) of the last edit
+    last_edit_time: Option<f64>,
 }
 
 impl History {
@@ -115,12 +164,37 @@ impl History {
         self.current_edit_kind = None;
     }
 
-    pub fn create_or_extend_edit_group(&mut self, edit_kind: EditKind, cursor: Cursor) {
-        if !self.current_edit_kind.map_or(false, |current_edit_kind| {
-            current_edit_kind.can_merge_with(edit_kind)
-        }) {
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        if max_depth > 0 {
+            self.undo_stack.trim(max_depth);
+            self.redo_stack.trim(max_depth);
+        }
+    }
+
+    pub fn set_group_window(&mut self, group_window: f64) {
+        self.group_window = group_window.max(0.0);
+    }
+
+    /// `time` is the time of the edit in seconds (`cx.seconds_since_app_start()` or the event time),
+    /// `std::time::Instant` is not used because it panics on wasm32
+    pub fn create_or_extend_edit_group(&mut self, edit_kind: EditKind, cursor: Cursor, time: f64) {
+        let in_window = self.group_window <= 0.0
+            || self
+                .last_edit_time
+                .map_or(false, |last| time - last <= self.group_window);
+        self.last_edit_time = Some(time);
+
+        if !in_window
+            || !self.current_edit_kind.map_or(false, |current_edit_kind| {
+                current_edit_kind.can_merge_with(edit_kind)
+            })
+        {
             self.undo_stack.push_edit_group(cursor);
             self.current_edit_kind = Some(edit_kind);
+            if self.max_depth > 0 {
+                self.undo_stack.trim(self.max_depth);
+            }
         }
     }
 
@@ -163,9 +237,395 @@ impl History {
             None
         }
     }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
     pub fn clear(&mut self) {
         self.current_edit_kind = None;
+        self.last_edit_time = None;
         self.undo_stack.clear();
         self.redo_stack.clear();
     }
 }
+
+// -------------------------------------------------------------------------------------------------------------
+
+/// A fuzzy match of the query against one candidate
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FuzzyMatch {
+    /// the candidate text
+    pub text: String,
+    /// match score, bigger is better
+    pub score: i64,
+    /// byte indices of the matched chars in `text` (sorted)
+    pub indices: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    /// split the text into `(segment, is_matched)` runs, which is used to draw the highlighted chars
+    pub fn segments(&self) -> Vec<(&str, bool)> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        let mut current = None;
+        let mut matched = self.indices.iter().peekable();
+        for (index, _) in self.text.char_indices() {
+            let is_matched = matched.peek().map_or(false, |i| **i == index);
+            if is_matched {
+                matched.next();
+            }
+            match current {
+                Some(state) if state != is_matched => {
+                    segments.push((&self.text[start..index], state));
+                    start = index;
+                    current = Some(is_matched);
+                }
+                None => current = Some(is_matched),
+                _ => {}
+            }
+        }
+        if let Some(state) = current {
+            segments.push((&self.text[start..], state));
+        }
+        segments
+    }
+}
+
+/// ## fuzzy match the query with the candidate (case insensitive)
+/// every char in the query should appear in the candidate in order, otherwise return `None`
+/// - consecutive chars and chars at the start of a word get a bonus
+/// - gaps between matched chars get a penalty
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    if query_chars.peek().is_none() {
+        return None;
+    }
+    let mut indices = Vec::new();
+    let mut score = 0_i64;
+    let mut last_matched: Option<usize> = None;
+    let mut prev_char: Option<char> = None;
+
+    for (char_index, (byte_index, c)) in candidate.char_indices().enumerate() {
+        let Some(target) = query_chars.peek() else {
+            break;
+        };
+        if c.to_lowercase().any(|lc| lc == *target) {
+            score += 1;
+            match last_matched {
+                Some(last) if last + 1 == char_index => score += 4,
+                Some(last) => score -= (char_index - last - 1).min(3) as i64,
+                None => score -= char_index.min(3) as i64,
+            }
+            if prev_char.map_or(true, |p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            indices.push(byte_index);
+            last_matched = Some(char_index);
+            query_chars.next();
+        }
+        prev_char = Some(c);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+
+    Some(FuzzyMatch {
+        text: candidate.to_string(),
+        score,
+        indices,
+    })
+}
+
+/// ## fuzzy filter the candidates
+/// return the matched candidates sorted by score (shorter text first if the score is equal),
+/// at most `limit` items
+pub fn fuzzy_filter<'a, I>(query: &str, candidates: I, limit: usize) -> Vec<FuzzyMatch>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut matches = candidates
+        .into_iter()
+        .filter_map(|candidate| fuzzy_match(query, candidate))
+        .collect::<Vec<FuzzyMatch>>();
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.text.len().cmp(&b.text.len()))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+// -------------------------------------------------------------------------------------------------------------
+
+/// Separators used to format and parse a number for a locale
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberLocale {
+    /// decimal separator
+    pub decimal: char,
+    /// group (thousands) separator, `None` means no grouping
+    pub group: Option<char>,
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self {
+            decimal: '.',
+            group: Some(','),
+        }
+    }
+}
+
+impl NumberLocale {
+    /// ## get the separators from a BCP 47 like locale tag, such as `en-US`, `de-DE`, `fr`
+    /// unknown tags fall back to `en`
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        let lang = tag.split('-').next().unwrap_or_default();
+        match (lang, tag.as_str()) {
+            (_, "de-ch") | (_, "it-ch") | (_, "fr-ch") => Self {
+                decimal: '.',
+                group: Some('\''),
+            },
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "vi", _) => Self {
+                decimal: ',',
+                group: Some('.'),
+            },
+            ("fr" | "ru" | "pl" | "cs" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" | "sk", _) => {
+                Self {
+                    decimal: ',',
+                    group: Some(' '),
+                }
+            }
+            _ => Self::default(),
+        }
+    }
+    /// ## format the value with fixed `precision` decimals
+    /// if `grouping` is false, the group separator is not used
+    pub fn format(&self, value: f64, precision: usize, grouping: bool) -> String {
+        let raw = format!("{:.*}", precision, value.abs());
+        let (int_part, frac_part) = raw.split_once('.').unwrap_or((raw.as_str(), ""));
+        let mut res = String::new();
+        if value < 0.0 && raw.chars().any(|c| c != '0' && c != '.') {
+            res.push('-');
+        }
+        match self.group.filter(|_| grouping) {
+            Some(group) => {
+                let len = int_part.len();
+                for (index, c) in int_part.chars().enumerate() {
+                    if index > 0 && (len - index) % 3 == 0 {
+                        res.push(group);
+                    }
+                    res.push(c);
+                }
+            }
+            None => res.push_str(int_part),
+        }
+        if !frac_part.is_empty() {
+            res.push(self.decimal);
+            res.push_str(frac_part);
+        }
+        res
+    }
+    /// ## parse a text formatted by this locale
+    /// group separators are ignored, `.` is always accepted as decimal separator if the locale's one is not in the text
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let decimal = if text.contains(self.decimal) {
+            self.decimal
+        } else {
+            '.'
+        };
+        let normalized = text
+            .chars()
+            .filter(|c| Some(*c) != self.group || *c == decimal)
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == decimal { '.' } else { c })
+            .collect::<String>();
+        normalized.parse::<f64>().ok().filter(|v| v.is_finite())
+    }
+    /// ## is the char can be typed into a number input of this locale
+    pub fn is_number_char(&self, c: char) -> bool {
+        c.is_ascii_digit()
+            || c == '-'
+            || c == '.'
+            || c == self.decimal
+            || Some(c) == self.group
+    }
+}
+
+/// ## clamp the value into `[min, max]`, snap it to `step` (from `min`, or `0.0` if `min` is infinite) and round it to `precision` decimals
+/// `step <= 0.0` means no snapping
+pub fn clamp_number(value: f64, min: f64, max: f64, step: f64, precision: usize) -> f64 {
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    let mut value = value.clamp(min, max);
+    if step > 0.0 {
+        let origin = if min.is_finite() { min } else { 0.0 };
+        value = origin + ((value - origin) / step).round() * step;
+        if value > max {
+            value -= step;
+        }
+    }
+    let factor = 10_f64.powi(precision.min(15) as i32);
+    ((value * factor).round() / factor).clamp(min, max)
+}
+
+/// ## commit the typed text of a number input
+/// the parsed value is clamped by `clamp`, invalid text keeps `current`,
+/// back the committed value and the text which replaces the typed one
+pub fn commit_number<F>(
+    locale: NumberLocale,
+    text: &str,
+    current: f64,
+    clamp: F,
+    precision: usize,
+    grouping: bool,
+) -> (f64, String)
+where
+    F: Fn(f64) -> f64,
+{
+    let value = locale.parse(text).map_or(current, clamp);
+    (value, locale.format(value, precision, grouping))
+}
+
+#[cfg(test)]
+mod test_history {
+    use makepad_widgets::Cursor;
+
+    use super::{Edit, EditKind, History};
+
+    fn insert(history: &mut History, text: &mut String, s: &str, time: f64) {
+        let start = text.len();
+        history.create_or_extend_edit_group(EditKind::Insert, Cursor::default(), time);
+        history.apply_edit(
+            Edit {
+                start,
+                end: start,
+                replace_with: s.to_string(),
+            },
+            text,
+        );
+    }
+
+    #[test]
+    fn max_depth() {
+        let mut history = History::default();
+        history.set_max_depth(2);
+        let mut text = String::new();
+        for s in ["a", "b", "c"] {
+            insert(&mut history, &mut text, s, 0.0);
+            history.force_new_edit_group();
+        }
+        // only the last 2 groups can be undone
+        assert!(history.undo(Cursor::default(), &mut text).is_some());
+        assert!(history.undo(Cursor::default(), &mut text).is_some());
+        assert!(history.undo(Cursor::default(), &mut text).is_none());
+        assert_eq!(text, "a");
+    }
+
+    #[test]
+    fn group_window() {
+        let mut history = History::default();
+        history.set_group_window(0.5);
+        let mut text = String::new();
+        insert(&mut history, &mut text, "a", 0.0);
+        insert(&mut history, &mut text, "b", 0.3);
+        // out of the window, a new group
+        insert(&mut history, &mut text, "c", 1.0);
+        assert!(history.undo(Cursor::default(), &mut text).is_some());
+        assert_eq!(text, "ab");
+        assert!(history.undo(Cursor::default(), &mut text).is_some());
+        assert_eq!(text, "");
+    }
+}
+
+#[cfg(test)]
+mod test_fuzzy {
+    use super::{fuzzy_filter, fuzzy_match};
+
+    #[test]
+    fn subsequence() {
+        let m = fuzzy_match("gnp", "GenUI Popup").unwrap();
+        assert_eq!(m.indices, vec![0, 2, 6]);
+        assert!(fuzzy_match("xyz", "GenUI Popup").is_none());
+        assert!(fuzzy_match("", "GenUI").is_none());
+    }
+
+    #[test]
+    fn segments() {
+        let m = fuzzy_match("ab", "abc").unwrap();
+        assert_eq!(m.segments(), vec![("ab", true), ("c", false)]);
+        let m = fuzzy_match("c", "abc").unwrap();
+        assert_eq!(m.segments(), vec![("ab", false), ("c", true)]);
+    }
+
+    #[test]
+    fn ranking() {
+        let candidates = vec![
+            "table".to_string(),
+            "tab".to_string(),
+            "toolbar".to_string(),
+        ];
+        let res = fuzzy_filter("tab", &candidates, 2);
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].text, "tab");
+        assert_eq!(res[1].text, "table");
+    }
+}
+
+#[cfg(test)]
+mod test_number {
+    use super::{clamp_number, commit_number, NumberLocale};
+
+    #[test]
+    fn format() {
+        let en = NumberLocale::from_tag("en-US");
+        assert_eq!(en.format(1234567.891, 2, true), "1,234,567.89");
+        assert_eq!(en.format(-12.0, 0, true), "-12");
+        assert_eq!(en.format(-0.001, 2, true), "0.00");
+        let de = NumberLocale::from_tag("de_DE");
+        assert_eq!(de.format(1234.5, 1, true), "1.234,5");
+        assert_eq!(de.format(1234.5, 1, false), "1234,5");
+    }
+
+    #[test]
+    fn parse() {
+        let en = NumberLocale::from_tag("en");
+        assert_eq!(en.parse("1,234.5"), Some(1234.5));
+        assert_eq!(en.parse("abc"), None);
+        let de = NumberLocale::from_tag("de");
+        assert_eq!(de.parse("1.234,5"), Some(1234.5));
+        let fr = NumberLocale::from_tag("fr-FR");
+        assert_eq!(fr.parse("1 234,5"), Some(1234.5));
+    }
+
+    #[test]
+    fn clamp() {
+        assert_eq!(clamp_number(12.0, 0.0, 10.0, 1.0, 0), 10.0);
+        assert_eq!(clamp_number(0.26, 0.0, 1.0, 0.1, 1), 0.3);
+        assert_eq!(clamp_number(9.8, 0.0, 10.0, 3.0, 0), 9.0);
+        assert_eq!(clamp_number(-2.4, f64::NEG_INFINITY, f64::INFINITY, 0.5, 1), -2.5);
+    }
+
+    #[test]
+    fn commit() {
+        let en = NumberLocale::default();
+        let clamp = |v| clamp_number(v, 0.0, 10.0, 1.0, 0);
+        // "100" is over max, the text is replaced by the clamped value
+        assert_eq!(commit_number(en, "100", 3.0, clamp, 0, false), (10.0, "10".to_string()));
+        // invalid text is reverted to the current value
+        assert_eq!(commit_number(en, "1a", 3.0, clamp, 0, false), (3.0, "3".to_string()));
+        let de = NumberLocale::from_tag("de");
+        let clamp = |v| clamp_number(v, 0.0, 10000.0, 0.0, 1);
+        assert_eq!(commit_number(de, "1234,56", 0.0, clamp, 1, true), (1234.6, "1.234,6".to_string()));
+    }
+}
@@ -0,0 +1,607 @@
+use makepad_widgets::*;
+
+use crate::{
+    components::{popup::PopupStackGlobal, view::GView},
+    shader::{draw_view::DrawGView, manual::MenuItemMode},
+    utils::{BoolToF32, ThemeColor},
+};
+
+use super::{
+    event::{GContextMenuEvent, GContextMenuSelectedParam},
+    menu_item::GMenuItemWidgetRefExt,
+    sub_menu::GSubMenuWidgetRefExt,
+    GMenu,
+};
+
+live_design! {
+    link gen_base;
+
+    pub GContextMenuBase = {{GContextMenu}}{
+        animation_key: false,
+    }
+}
+
+/// a child of a menu level: its id, the widget and its mode
+type MenuRow = (LiveId, WidgetRef, MenuItemMode);
+
+/// # GContextMenu component
+/// A right-click menu, it wraps a view and opens at the pointer on secondary click.
+/// The items are a `GMenu`, the children of its `body` are the root level:
+/// ```
+/// <GContextMenu>{
+///     menu: {
+///         body: {
+///             copy = <GContextMenuItem>{ text_slot: {text: "Copy"}, right: {shortcut = {text: "Ctrl+C"}} }
+///             paste = <GContextMenuItem>{ disabled: true, text_slot: {text: "Paste"} }
+///             <GDivider>{}
+///             share = <GContextSubMenu>{
+///                 title: { label = {text: "Share"} }
+///                 items: {
+///                     mail = <GContextMenuItem>{ text_slot: {text: "Mail"} }
+///                 }
+///             }
+///         }
+///     }
+///     <GView>{ ... }
+/// }
+/// ```
+/// - the levels follow `MenuItemMode`: `GMenuItem` is an item, `GSubMenu` opens its `items` as the next level,
+///   `GDivider` is a separator. Only the `title` of a `GSubMenu` is drawn, its items cascade in an own panel
+/// - sub menus cascade on hover, they flip to the left side if there is no space on the right
+/// - `Up` / `Down` move the highlight, `Right` / `Enter` open a sub menu, `Left` / `Esc` close it,
+///   `Enter` / `Space` choose the item, `Esc` on the root closes the menu
+///
+/// ## Event
+/// - `Selected(GContextMenuSelectedParam)`: an item is chosen, with its id and path
+#[derive(Live, Widget)]
+pub struct GContextMenu {
+    #[deref]
+    pub deref_widget: GView,
+    /// the items, a `GMenu` whose `body` holds `GMenuItem`, `GSubMenu` and `GDivider`
+    #[live]
+    pub menu: Option<LivePtr>,
+    #[live]
+    pub disabled_color: Option<Vec4>,
+    #[live(32.0)]
+    pub item_height: f64,
+    #[live(9.0)]
+    pub separator_height: f64,
+    /// open on secondary click, set `false` to open by code with `open_at`
+    #[live(true)]
+    pub open_on_secondary: bool,
+    #[live]
+    pub draw_backdrop: DrawGView,
+    /// the highlight of the `GSubMenu` titles
+    #[live]
+    pub draw_highlight: DrawGView,
+    #[live]
+    pub draw_list: DrawList2d,
+    #[live(true)]
+    pub event_key: bool,
+    #[rust]
+    root: Option<GMenu>,
+    #[rust]
+    pub opened: bool,
+    #[rust]
+    origin: DVec2,
+    /// the highlighted item of each level, `path[..expanded]` are the opened sub menus
+    #[rust]
+    path: Vec<usize>,
+    /// count of the opened sub menus
+    #[rust]
+    expanded: usize,
+    /// rects of the drawn levels
+    #[rust]
+    panels: Vec<Rect>,
+    /// items are chosen by the mouse only after the mouse moves or is pressed again
+    #[rust]
+    armed: bool,
+    #[rust]
+    lock_pending: bool,
+}
+
+impl LiveHook for GContextMenu {
+    fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) {
+        self.deref_widget.after_apply(cx, apply, index, nodes);
+    }
+    fn after_apply_from_doc(&mut self, cx: &mut Cx) {
+        self.deref_widget.after_apply_from_doc(cx);
+        self.root = self.menu.is_some().then(|| GMenu::new_from_ptr(cx, self.menu));
+    }
+}
+
+impl Widget for GContextMenu {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        if !self.visible {
+            return DrawStep::done();
+        }
+        let _ = self.deref_widget.draw_walk(cx, scope, walk);
+        if self.opened {
+            self.draw_menu_levels(cx, scope);
+        }
+        DrawStep::done()
+    }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.visible {
+            return;
+        }
+        if self.opened {
+            // items only play their animations, the hits are done here
+            for level in 0..=self.expanded {
+                for (_, widget, mode) in self.rows(level) {
+                    if !mode.is_menu_item() {
+                        continue;
+                    }
+                    if let Some(mut item) = widget.as_gmenu_item().borrow_mut() {
+                        if item.animator_handle_event(cx, event).must_redraw() {
+                            item.redraw(cx);
+                        }
+                    }
+                }
+            }
+            self.handle_menu_event(cx, event, scope);
+        }
+        self.deref_widget.handle_event(cx, event, scope);
+        if self.opened || !self.open_on_secondary {
+            return;
+        }
+        if let Hit::FingerDown(e) = event.hits_with_options(
+            cx,
+            self.area(),
+            HitOptions::new().with_capture_overload(true),
+        ) {
+            if e.mouse_button().is_some_and(|button| button.is_secondary()) {
+                self.open_at(cx, e.abs);
+            }
+        }
+    }
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl GContextMenu {
+    pub fn area(&self) -> Area {
+        self.deref_widget.area
+    }
+    pub fn selected(&self, actions: &Actions) -> Option<GContextMenuSelectedParam> {
+        if !self.event_key {
+            return None;
+        }
+        if let GContextMenuEvent::Selected(e) = actions.find_widget_action(self.widget_uid()).cast()
+        {
+            Some(e)
+        } else {
+            None
+        }
+    }
+    /// disable (or enable) the item or sub menu at `path`
+    pub fn set_item_disabled(&mut self, cx: &mut Cx, path: &[usize], disabled: bool) {
+        let Some(root) = self.root.as_mut() else {
+            return;
+        };
+        MenuItemMode::find_node(&mut root.body.children, &path.to_vec(), &mut |widget| {
+            if let Some(mut item) = widget.as_gmenu_item().borrow_mut() {
+                item.disabled = disabled;
+            } else if let Some(mut sub_menu) = widget.as_gsub_menu().borrow_mut() {
+                sub_menu.disabled = disabled;
+            }
+        });
+        if self.opened {
+            self.draw_list.redraw(cx);
+        }
+    }
+    /// open the menu at `pos` (absolute position in the window)
+    pub fn open_at(&mut self, cx: &mut Cx, pos: DVec2) {
+        if self.rows(0).is_empty() {
+            return;
+        }
+        self.opened = true;
+        self.origin = pos;
+        self.path.clear();
+        self.expanded = 0;
+        self.armed = false;
+        self.lock_pending = true;
+        self.draw_list.redraw(cx);
+        self.redraw(cx);
+    }
+    pub fn close(&mut self, cx: &mut Cx) {
+        if !self.opened {
+            return;
+        }
+        self.path.clear();
+        self.fresh_hover(cx);
+        self.opened = false;
+        self.expanded = 0;
+        self.lock_pending = false;
+        PopupStackGlobal::close(cx, self.widget_uid());
+        self.draw_list.redraw(cx);
+        self.redraw(cx);
+    }
+    fn draw_menu_levels(&mut self, cx: &mut Cx2d, scope: &mut Scope) {
+        let Some(mut root) = self.root.take() else {
+            return;
+        };
+        let uid = self.widget_uid();
+        if PopupStackGlobal::take_raise(cx, uid) {
+            self.draw_list.begin_overlay_last(cx);
+        } else {
+            self.draw_list.begin_overlay_reuse(cx);
+        }
+        cx.begin_pass_sized_turtle(Layout::flow_down());
+        let viewport = cx.current_pass_size();
+        // the backdrop is not visible, it holds the sweep lock
+        self.draw_backdrop.background_visible = 0.0;
+        self.draw_backdrop.draw_walk(cx, Walk::fill());
+
+        self.draw_highlight.background_color = root.hover_color.get(root.theme, 400);
+        let disabled_color = self.disabled_color.use_or("#667085").unwrap();
+        let width = menu_width(&root);
+        let padding = root.layout.padding;
+
+        self.panels.clear();
+        for level in 0..=self.expanded {
+            let rows = menu_rows(&root, &self.path, level);
+            let height = padding.top
+                + padding.bottom
+                + rows.iter().map(|(_, _, mode)| self.height_of(mode)).sum::<f64>();
+            let pos = if level == 0 {
+                self.root_pos(width, height, viewport)
+            } else {
+                let parent = menu_rows(&root, &self.path, level - 1);
+                self.sub_pos(level, &parent, width, height, viewport)
+            };
+            self.panels.push(Rect {
+                pos,
+                size: dvec2(width, height),
+            });
+            let _ = root.draw_menu.begin(
+                cx,
+                Walk {
+                    abs_pos: Some(pos),
+                    width: Size::Fixed(width),
+                    height: Size::Fixed(height),
+                    ..Default::default()
+                },
+                Layout {
+                    flow: Flow::Down,
+                    padding,
+                    ..Default::default()
+                },
+            );
+            for (index, (_, widget, mode)) in rows.iter().enumerate() {
+                let walk = Walk {
+                    width: Size::Fill,
+                    height: Size::Fixed(self.height_of(mode)),
+                    ..Default::default()
+                };
+                match mode {
+                    MenuItemMode::MenuItem(_) => {
+                        if let Some(mut item) = widget.as_gmenu_item().borrow_mut() {
+                            if item.disabled {
+                                item.text_slot.draw_text.color = disabled_color;
+                            } else {
+                                let _ = item.text_slot.render(cx);
+                            }
+                        }
+                        let _ = widget.draw_walk(cx, scope, walk);
+                    }
+                    MenuItemMode::SubMenu(_) => {
+                        let highlighted = self.path.get(level) == Some(&index);
+                        self.draw_highlight.background_visible = highlighted.to_f32();
+                        let _ = self.draw_highlight.begin(cx, walk, Layout::flow_right());
+                        if let Some(mut sub_menu) = widget.as_gsub_menu().borrow_mut() {
+                            let _ = sub_menu.title.draw_walk(cx, scope, Walk::fill());
+                        }
+                        let _ = self.draw_highlight.end(cx);
+                    }
+                    MenuItemMode::Separator => {
+                        let _ = widget.draw_walk(cx, scope, walk);
+                    }
+                }
+            }
+            let _ = root.draw_menu.end(cx);
+        }
+        cx.end_pass_sized_turtle_with_shift(Area::Empty, DVec2::default());
+        self.draw_list.end(cx);
+        self.root = Some(root);
+
+        if self.lock_pending {
+            self.lock_pending = false;
+            PopupStackGlobal::open(cx, uid, self.draw_backdrop.area());
+            self.draw_list.redraw(cx);
+        }
+    }
+    fn handle_menu_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !PopupStackGlobal::accepts(cx, self.widget_uid(), event) {
+            return;
+        }
+        match event {
+            Event::MouseMove(e) => {
+                self.armed = true;
+                if let Some((level, index)) = self.item_at(e.abs) {
+                    self.highlight(cx, level, index, true);
+                }
+            }
+            Event::MouseDown(e) => {
+                self.armed = true;
+                let over = self.panels.iter().any(|panel| panel.contains(e.abs));
+                if !over && PopupStackGlobal::is_top(cx, self.widget_uid()) {
+                    self.close(cx);
+                }
+            }
+            Event::MouseUp(e) if self.armed => {
+                if let Some((level, index)) = self.item_at(e.abs) {
+                    self.activate(cx, scope, level, index);
+                }
+            }
+            Event::KeyDown(e) if PopupStackGlobal::is_top(cx, self.widget_uid()) => {
+                let level = self.expanded;
+                match e.key_code {
+                    KeyCode::ArrowDown | KeyCode::ArrowUp => {
+                        let down = e.key_code == KeyCode::ArrowDown;
+                        let enabled = self.enabled(level);
+                        if let Some(index) =
+                            next_enabled(&enabled, self.path.get(level).copied(), down)
+                        {
+                            self.highlight(cx, level, index, false);
+                        }
+                    }
+                    KeyCode::ArrowRight => self.expand(cx),
+                    KeyCode::ArrowLeft => self.collapse_last(cx),
+                    KeyCode::Escape => {
+                        if self.expanded > 0 {
+                            self.collapse_last(cx);
+                        } else {
+                            self.close(cx);
+                        }
+                    }
+                    KeyCode::ReturnKey | KeyCode::Space => {
+                        if let Some(index) = self.path.get(level).copied() {
+                            self.activate(cx, scope, level, index);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+    /// the rows of a level, following the opened sub menus
+    fn rows(&self, level: usize) -> Vec<MenuRow> {
+        self.root
+            .as_ref()
+            .map_or_else(Vec::new, |root| menu_rows(root, &self.path, level))
+    }
+    fn height_of(&self, mode: &MenuItemMode) -> f64 {
+        if mode.is_separator() {
+            self.separator_height
+        } else {
+            self.item_height
+        }
+    }
+    /// the root is at the pointer and stays in the window
+    fn root_pos(&self, width: f64, height: f64, viewport: DVec2) -> DVec2 {
+        let x = self.origin.x.min(viewport.x - width).max(0.0);
+        let y = if self.origin.y + height > viewport.y && self.origin.y >= height {
+            self.origin.y - height
+        } else {
+            self.origin.y.min(viewport.y - height).max(0.0)
+        };
+        dvec2(x, y)
+    }
+    /// a sub menu is at the right (or left) of its item in `parent` and stays in the window
+    fn sub_pos(
+        &self,
+        level: usize,
+        parent: &[MenuRow],
+        width: f64,
+        height: f64,
+        viewport: DVec2,
+    ) -> DVec2 {
+        let panel = self.panels[level - 1];
+        let index = self.path[level - 1];
+        let item_y = panel.pos.y
+            + parent[..index]
+                .iter()
+                .map(|(_, _, mode)| self.height_of(mode))
+                .sum::<f64>();
+        let mut x = panel.pos.x + panel.size.x;
+        if x + width > viewport.x {
+            x = (panel.pos.x - width).max(0.0);
+        }
+        dvec2(x, item_y.min(viewport.y - height).max(0.0))
+    }
+    fn item_at(&self, pos: DVec2) -> Option<(usize, usize)> {
+        let top = self.root.as_ref()?.layout.padding.top;
+        // upper levels first, they are drawn above
+        for (level, panel) in self.panels.iter().enumerate().rev() {
+            if !panel.contains(pos) {
+                continue;
+            }
+            let mut y = panel.pos.y + top;
+            for (index, row) in self.rows(level).iter().enumerate() {
+                let height = self.height_of(&row.2);
+                if pos.y >= y && pos.y < y + height {
+                    return is_enabled(row).then_some((level, index));
+                }
+                y += height;
+            }
+            return None;
+        }
+        None
+    }
+    /// highlight an item, with `open_sub` an enabled sub menu item opens its sub menu (hover)
+    fn highlight(&mut self, cx: &mut Cx, level: usize, index: usize, open_sub: bool) {
+        // back on the item of the opened sub menu, keep it
+        if self.expanded > level && self.path.get(level) == Some(&index) {
+            return;
+        }
+        if self.path.len() == level + 1 && self.path[level] == index && self.expanded == level {
+            return;
+        }
+        let deeper = self.expanded;
+        self.path.truncate(level);
+        self.path.push(index);
+        self.expanded = self.expanded.min(level);
+        if open_sub && self.rows(level)[index].2.is_sub_menu() {
+            self.expanded = level + 1;
+        }
+        self.fresh_hover_to(cx, deeper);
+    }
+    /// open the sub menu of the highlighted item and highlight its first enabled item
+    fn expand(&mut self, cx: &mut Cx) {
+        let level = self.expanded;
+        let Some(index) = self.path.get(level).copied() else {
+            return;
+        };
+        let rows = self.rows(level);
+        if !rows[index].2.is_sub_menu() || !is_enabled(&rows[index]) {
+            return;
+        }
+        self.expanded = level + 1;
+        self.path.truncate(level + 1);
+        let enabled = self.enabled(level + 1);
+        if let Some(first) = next_enabled(&enabled, None, true) {
+            self.path.push(first);
+        }
+        self.fresh_hover(cx);
+    }
+    fn collapse_last(&mut self, cx: &mut Cx) {
+        if self.expanded == 0 {
+            return;
+        }
+        let deeper = self.expanded;
+        self.path.truncate(self.expanded);
+        self.expanded -= 1;
+        self.fresh_hover_to(cx, deeper);
+    }
+    fn enabled(&self, level: usize) -> Vec<bool> {
+        self.rows(level).iter().map(is_enabled).collect()
+    }
+    fn activate(&mut self, cx: &mut Cx, scope: &mut Scope, level: usize, index: usize) {
+        let row = self.rows(level)[index].clone();
+        if !is_enabled(&row) {
+            return;
+        }
+        match row {
+            (id, _, MenuItemMode::MenuItem(_)) => {
+                let mut path = self.path[..level].to_vec();
+                path.push(index);
+                self.close(cx);
+                if self.event_key {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        GContextMenuEvent::Selected(GContextMenuSelectedParam { id, path }),
+                    );
+                }
+            }
+            (_, _, MenuItemMode::SubMenu(_)) => {
+                self.highlight(cx, level, index, false);
+                self.expand(cx);
+            }
+            (_, _, MenuItemMode::Separator) => (),
+        }
+    }
+    fn fresh_hover(&mut self, cx: &mut Cx) {
+        self.fresh_hover_to(cx, self.expanded);
+    }
+    /// play the hover of the items in the levels up to `last`, only the highlighted items of
+    /// the opened levels are on
+    fn fresh_hover_to(&mut self, cx: &mut Cx, last: usize) {
+        for level in 0..=last {
+            for (index, (_, widget, mode)) in self.rows(level).iter().enumerate() {
+                if !mode.is_menu_item() {
+                    continue;
+                }
+                let on = level <= self.expanded && self.path.get(level) == Some(&index);
+                if let Some(mut item) = widget.as_gmenu_item().borrow_mut() {
+                    item.animator_play(cx, if on { id!(hover.on) } else { id!(hover.off) });
+                }
+            }
+        }
+        self.draw_list.redraw(cx);
+    }
+}
+
+/// the rows of `level` in `root`, `path` holds the opened sub menu of each upper level
+fn menu_rows(root: &GMenu, path: &[usize], level: usize) -> Vec<MenuRow> {
+    let mut children = root.body.children.clone();
+    let mut modes = root.item_modes.clone();
+    for index in path.iter().take(level) {
+        let Some(MenuItemMode::SubMenu(sub_modes)) = modes.get(*index).cloned() else {
+            return Vec::new();
+        };
+        let Some(sub_children) = children
+            .get(*index)
+            .and_then(|(_, child)| child.as_gsub_menu().borrow().map(|sub| sub.items.children.clone()))
+        else {
+            return Vec::new();
+        };
+        children = sub_children;
+        modes = sub_modes;
+    }
+    children
+        .into_iter()
+        .zip(modes)
+        .map(|((id, widget), mode)| (id, widget, mode))
+        .collect()
+}
+
+/// the width of the panels, `GMenu` is usually `Fill` in a page so a fixed width is required
+fn menu_width(root: &GMenu) -> f64 {
+    match root.walk.width {
+        Size::Fixed(width) => width,
+        _ => 200.0,
+    }
+}
+
+/// can be highlighted and chosen
+fn is_enabled((_, widget, mode): &MenuRow) -> bool {
+    match mode {
+        MenuItemMode::MenuItem(_) => widget
+            .as_gmenu_item()
+            .borrow()
+            .is_some_and(|item| !item.disabled),
+        MenuItemMode::SubMenu(_) => widget
+            .as_gsub_menu()
+            .borrow()
+            .is_some_and(|sub_menu| !sub_menu.disabled),
+        MenuItemMode::Separator => false,
+    }
+}
+
+/// the next (or previous) enabled item after `current`, it wraps around
+fn next_enabled(enabled: &[bool], current: Option<usize>, down: bool) -> Option<usize> {
+    let len = enabled.len();
+    (1..=len)
+        .map(|step| match (current, down) {
+            (Some(current), true) => (current + step) % len,
+            (Some(current), false) => (current + len - step) % len,
+            (None, true) => step - 1,
+            (None, false) => len - step,
+        })
+        .find(|index| enabled[*index])
+}
+
+impl GContextMenuRef {
+    pub fn selected(&self, actions: &Actions) -> Option<GContextMenuSelectedParam> {
+        self.borrow().and_then(|c_ref| c_ref.selected(actions))
+    }
+    pub fn set_item_disabled(&self, cx: &mut Cx, path: &[usize], disabled: bool) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_item_disabled(cx, path, disabled);
+        }
+    }
+    pub fn open_at(&self, cx: &mut Cx, pos: DVec2) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.open_at(cx, pos);
+        }
+    }
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.close(cx);
+        }
+    }
+}
@@ -48,3 +48,18 @@ pub struct GMenuChangedParam {
     pub selected_id: LiveId,
     pub e: FingerUpEvent,
 }
+
+// --------------------------------------------------------------------------------
+#[derive(Debug, Clone, DefaultNone)]
+pub enum GContextMenuEvent {
+    Selected(GContextMenuSelectedParam),
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct GContextMenuSelectedParam {
+    /// id of the chosen item
+    pub id: LiveId,
+    /// indexes of the item from the root, same as `GMenu` selected
+    pub path: Vec<usize>,
+}
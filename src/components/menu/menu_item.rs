@@ -101,6 +101,9 @@ pub struct GMenuItem {
     pub walk: Walk,
     #[live(false)]
     pub selected: bool,
+    /// a disabled item plays no hover and is not clicked
+    #[live(false)]
+    pub disabled: bool,
     #[animator]
     pub animator: Animator,
     #[live(true)]
@@ -138,6 +141,9 @@ impl Widget for GMenuItem {
         if self.animator_handle_event(cx, event).must_redraw() {
             self.redraw(cx);
         }
+        if self.disabled {
+            return;
+        }
 
         match event.hits(cx, self.area()) {
             Hit::FingerDown(_) => {
@@ -1,9 +1,10 @@
+pub mod context_menu;
 pub mod event;
 pub mod menu_item;
 pub mod register;
 pub mod sub_menu;
 
-use super::view::GView;
+use super::{divider::GDividerWidgetRefExt, view::GView};
 use crate::{
     event_option, ref_event_option, set_event, shader::{draw_view::DrawGView, manual::MenuItemMode}, themes::Themes, utils::{BoolToF32, ThemeColor}
 };
@@ -138,6 +139,7 @@ impl Widget for GMenu {
                             }
                         });
                     }
+                    MenuItemMode::Separator => {}
                 }
                 if fresh.is_some() {
                     break;
@@ -210,6 +212,7 @@ impl GMenu {
                         item.clear_selected(cx);
                     });
                 }
+                MenuItemMode::Separator => {}
             }
         }
         // then if selected is not None, set the selected item
@@ -229,8 +232,10 @@ impl GMenu {
             } else if let Some(child) = child.as_gsub_menu().borrow() {
                 self.item_modes
                     .push(MenuItemMode::SubMenu(child.item_modes.clone()));
+            } else if child.as_gdivider().borrow().is_some() {
+                self.item_modes.push(MenuItemMode::Separator);
             } else {
-                panic!("GMenu only allows GMenuItem or GSubMenu as child!");
+                panic!("GMenu only allows GMenuItem, GSubMenu or GDivider as child!");
             }
         }
         self.selected = MenuItemMode::selected(&self.item_modes);
@@ -4,4 +4,5 @@ pub fn register(cx: &mut Cx) {
     crate::components::menu::menu_item::live_design(cx);
     crate::components::menu::sub_menu::live_design(cx);
     crate::components::menu::live_design(cx);
+    crate::components::menu::context_menu::live_design(cx);
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use makepad_widgets::*;
 
 use crate::{
-    components::view::GView,
+    components::{divider::GDividerWidgetRefExt, view::GView},
     event_option, ref_event_option, set_event,
     shader::{draw_view::DrawGView, manual::MenuItemMode},
     themes::Themes,
@@ -59,6 +59,9 @@ pub struct GSubMenu {
     pub cursor: Option<MouseCursor>,
     #[live(true)]
     pub visible: bool,
+    /// a disabled sub menu can not be opened from `GContextMenu`
+    #[live(false)]
+    pub disabled: bool,
     #[redraw]
     #[live]
     pub draw_sub_menu: DrawGView,
@@ -137,6 +140,7 @@ impl Widget for GSubMenu {
                             }
                         });
                     }
+                    MenuItemMode::Separator => {}
                 }
             }
             if let Some((id, e)) = fresh {
@@ -189,6 +193,7 @@ impl GSubMenu {
                         item.clear_selected(cx);
                     });
                 }
+                MenuItemMode::Separator => {}
             }
         }
     }
@@ -213,6 +218,7 @@ impl GSubMenu {
                         item.render(cx);
                     });
                 }
+                MenuItemMode::Separator => {}
             }
         }
         // then if selected is not None, set the selected item
@@ -233,8 +239,10 @@ impl GSubMenu {
             } else if let Some(child) = child.as_gsub_menu().borrow() {
                 self.item_modes
                     .push(MenuItemMode::SubMenu(child.item_modes.clone()));
+            } else if child.as_gdivider().borrow().is_some() {
+                self.item_modes.push(MenuItemMode::Separator);
             } else {
-                panic!("GSubMenu only allows GMenuItem or GSubMenu as child!");
+                panic!("GSubMenu only allows GMenuItem, GSubMenu or GDivider as child!");
             }
         }
         self.selected = MenuItemMode::selected(&self.item_modes);
@@ -0,0 +1,31 @@
+use makepad_widgets::{ActionDefaultRef, DefaultNone};
+
+use super::types::{DismissReason, ToastId};
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GNotificationEvent {
+    Dismissed(GNotificationDismissedParam),
+    Action(GNotificationActionParam),
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct GNotificationDismissedParam {
+    pub id: ToastId,
+    pub reason: DismissReason,
+}
+
+#[derive(Clone, Debug)]
+pub struct GNotificationActionParam {
+    pub id: ToastId,
+    /// index of the action in `Toast::actions`
+    pub index: usize,
+    pub text: String,
+}
+// -------------------------------------------------------------------------
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GToastEvent {
+    Closed,
+    Action(usize),
+    None,
+}
@@ -1,25 +1,507 @@
+mod event;
 pub mod register;
+pub mod toast;
+mod types;
 
+pub use event::*;
+pub use types::{DismissReason, NotificationRecord, Toast, ToastId};
 
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 use makepad_widgets::*;
+use toast::GToast;
+use types::NotificationCommand;
 
-use super::view::GView;
+use crate::{
+    event_option, ref_event_option, set_event, set_scope_path, shader::manual::Position,
+};
 
-live_design!{
-    GNotificationBase = {{GNotification}} {}
+live_design! {
+    link gen_base;
+
+    pub GNotificationBase = {{GNotification}}{
+        width: 0.0,
+        height: 0.0,
+        position: TopRight,
+    }
 }
 
+/// # GNotification component
+/// A global notification center, toasts are pushed from anywhere with `&mut Cx`
+/// and stacked in the corner set by `position`.
+///
+/// Put one `GNotification` in the root of the app (e.g. in the `GWindow`), it takes no space.
+/// ```rust
+/// let id = GNotification::push(cx, Toast::new("Uploaded").with_kind(Themes::Success));
+/// GNotification::dismiss(cx, id);
+/// ```
+/// - a toast is dismissed after its `duration`, the progress bar shows the remaining time
+/// - the countdown is paused while the mouse is over the toast
+///
+/// ## Queue
+/// At most `max_visible` toasts are on the screen, the others wait in a queue (at most `max_queued`,
+/// the oldest waiting toast is dropped when it is full). With `dedup`, a toast identical to one
+/// on the screen or in the queue is collapsed into it and shown with a `×N` counter.
+/// All toasts are kept in the history (at most `history_size`), see `GNotification::history`.
+///
+/// ## Rate limit
+/// With `rate_limit`, at most `rate_limit` toasts are shown in `rate_interval` seconds, the others
+/// wait in the queue, so a burst of toasts is shown one batch after another.
+///
+/// ## Dedup
+/// Every id of a collapsed toast gets its own `Dismissed`. Dismissing a collapsed id only lowers
+/// the counter, dismissing the first id (the one on the screen) dismisses all of them.
+///
+/// ## Event
+/// - `Dismissed(GNotificationDismissedParam)`: a toast is dismissed, with the reason
+/// - `Action(GNotificationActionParam)`: an action button of a toast is clicked (the toast is dismissed after)
 #[derive(Live, Widget, LiveHook)]
-pub struct GNotification{
-    #[deref]
+pub struct GNotification {
+    /// the corner (or side) where the toasts are stacked
+    #[live]
+    pub position: Position,
+    /// space between the toasts and the window edges
+    #[live(16.0)]
+    pub offset: f64,
+    /// space between the toasts
+    #[live(12.0)]
+    pub spacing: f64,
     #[live]
-    pub view: GView,
+    pub toast: Option<LivePtr>,
+    /// max count of the toasts on the screen, `0` means no limit
+    #[live(5)]
+    pub max_visible: usize,
+    /// max count of the waiting toasts, `0` means no limit
+    #[live(50)]
+    pub max_queued: usize,
+    /// collapse identical toasts into one
+    #[live(true)]
+    pub dedup: bool,
+    /// max count of the records in the history
+    #[live(100)]
+    pub history_size: usize,
+    /// max count of the toasts shown in `rate_interval`, `0` means no limit
+    #[live(0)]
+    pub rate_limit: usize,
+    /// seconds of the rate limit window
+    #[live(1.0)]
+    pub rate_interval: f64,
+    #[redraw]
+    #[live]
+    pub draw_list: DrawList2d,
+    #[walk]
+    pub walk: Walk,
+    #[live(true)]
+    pub visible: bool,
+    #[live(true)]
+    pub event_key: bool,
+    #[rust]
+    pub toasts: Vec<ToastState>,
+    /// toasts waiting for a place on the screen
+    #[rust]
+    pub queue: VecDeque<ToastState>,
+    #[rust]
+    views: ComponentMap<LiveId, GToast>,
+    #[rust]
+    next_frame: NextFrame,
+    #[rust]
+    last_time: Option<f64>,
+    /// when the toasts in the rate limit window are shown (seconds since the app start)
+    #[rust]
+    shown_times: VecDeque<f64>,
+    #[rust]
+    rate_timer: Timer,
+    #[rust]
+    pub scope_path: Option<HeapLiveIdPath>,
+}
+
+/// a toast which is on the screen
+#[derive(Debug, Clone)]
+pub struct ToastState {
+    pub id: ToastId,
+    pub toast: Toast,
+    /// remaining seconds
+    pub remaining: f64,
+    pub hovered: bool,
+    /// ids of the identical toasts collapsed into this one
+    pub merged: Vec<ToastId>,
+}
+
+impl ToastState {
+    pub fn count(&self) -> usize {
+        self.merged.len() + 1
+    }
+    pub fn has_id(&self, id: ToastId) -> bool {
+        self.id == id || self.merged.contains(&id)
+    }
 }
 
-impl Widget for GNotification{
-    fn draw_walk(&mut self, _cx: &mut Cx2d, _scope: &mut Scope, _walk: Walk) -> DrawStep {
+#[derive(Default, Clone)]
+pub struct NotificationGlobal {
+    pub(crate) commands: Rc<RefCell<Vec<NotificationCommand>>>,
+    pub(crate) next_id: Rc<RefCell<ToastId>>,
+    pub(crate) history: Rc<RefCell<VecDeque<NotificationRecord>>>,
+}
+
+impl Widget for GNotification {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, _walk: Walk) -> DrawStep {
+        if !self.visible {
+            return DrawStep::done();
+        }
+        self.set_scope_path(&scope.path);
+        self.apply_commands(cx);
+
+        self.draw_list.begin_overlay_reuse(cx);
+        cx.begin_pass_sized_turtle(Layout {
+            flow: Flow::Down,
+            spacing: self.spacing,
+            padding: Padding {
+                left: self.offset,
+                right: self.offset,
+                top: self.offset,
+                bottom: self.offset,
+            },
+            align: self.align(),
+            ..Default::default()
+        });
+        // the newest toast is the nearest to the window edge
+        let reverse = self.align().y == 0.0;
+        let len = self.toasts.len();
+        for i in 0..len {
+            let state = &self.toasts[if reverse { len - 1 - i } else { i }];
+            let view = self
+                .views
+                .get_or_insert(cx, LiveId(state.id), |cx| GToast::new_from_ptr(cx, self.toast));
+            let progress = if state.toast.duration > 0.0 {
+                Some(state.remaining / state.toast.duration)
+            } else {
+                None
+            };
+            view.draw_toast(cx, scope, &state.toast, state.count(), progress);
+        }
+        cx.end_pass_sized_turtle_with_shift(Area::Empty, DVec2::default());
+        self.draw_list.end(cx);
         DrawStep::done()
     }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.visible {
+            return;
+        }
+        if let Some(ne) = self.next_frame.is_event(event) {
+            self.tick(cx, ne.time);
+        }
+        if self.rate_timer.is_event(event).is_some() {
+            self.show_queued(cx);
+            self.redraw(cx);
+        }
+        if let Event::MouseMove(e) = event {
+            for (id, view) in self.views.iter() {
+                let hovered = view.area().clipped_rect(cx).contains(e.abs);
+                if let Some(state) = self.toasts.iter_mut().find(|state| state.id == id.0) {
+                    state.hovered = hovered;
+                }
+            }
+        }
+        let mut actions = Vec::new();
+        for (id, view) in self.views.iter_mut() {
+            view.handle_event_with(cx, event, scope, &mut |_, e| actions.push((id.0, e)));
+        }
+        for (id, action) in actions {
+            match action {
+                GToastEvent::Closed => self.dismiss_toast(cx, id, DismissReason::Closed),
+                GToastEvent::Action(index) => {
+                    let text = self
+                        .toasts
+                        .iter()
+                        .find(|state| state.id == id)
+                        .and_then(|state| state.toast.actions.get(index).cloned())
+                        .unwrap_or_default();
+                    self.emit(
+                        cx,
+                        GNotificationEvent::Action(GNotificationActionParam { id, index, text }),
+                    );
+                    self.dismiss_toast(cx, id, DismissReason::Action);
+                }
+                GToastEvent::None => (),
+            }
+        }
+    }
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl GNotification {
+    set_scope_path!();
+    event_option! {
+        dismissed: GNotificationEvent::Dismissed => GNotificationDismissedParam,
+        action: GNotificationEvent::Action => GNotificationActionParam
+    }
+    /// ## push a toast to the notification center, back the id of the toast
+    /// it can be called from anywhere with `&mut Cx`, the toast is shown in next draw
+    pub fn push(cx: &mut Cx, toast: Toast) -> ToastId {
+        let global = cx.global::<NotificationGlobal>().clone();
+        let id = {
+            let mut next_id = global.next_id.borrow_mut();
+            *next_id += 1;
+            *next_id
+        };
+        global
+            .commands
+            .borrow_mut()
+            .push(NotificationCommand::Push(id, toast));
+        cx.redraw_all();
+        id
+    }
+    /// ## dismiss a toast by id, `Dismissed` is sent with `DismissReason::Manual`
+    /// if the id is collapsed into another toast, only the counter is lowered
+    pub fn dismiss(cx: &mut Cx, id: ToastId) {
+        let global = cx.global::<NotificationGlobal>().clone();
+        global
+            .commands
+            .borrow_mut()
+            .push(NotificationCommand::Dismiss(id));
+        cx.redraw_all();
+    }
+    /// dismiss all toasts
+    pub fn clear(cx: &mut Cx) {
+        let global = cx.global::<NotificationGlobal>().clone();
+        global.commands.borrow_mut().push(NotificationCommand::Clear);
+        cx.redraw_all();
+    }
+    fn apply_commands(&mut self, cx: &mut Cx) {
+        let global = cx.global::<NotificationGlobal>().clone();
+        let commands: Vec<NotificationCommand> = global.commands.borrow_mut().drain(..).collect();
+        for command in commands {
+            match command {
+                NotificationCommand::Push(id, toast) => self.push_toast(cx, id, toast),
+                NotificationCommand::Dismiss(id) => {
+                    self.dismiss_toast(cx, id, DismissReason::Manual);
+                }
+                NotificationCommand::Clear => {
+                    let ids: Vec<ToastId> = self
+                        .toasts
+                        .iter()
+                        .chain(self.queue.iter())
+                        .map(|state| state.id)
+                        .collect();
+                    for id in ids {
+                        self.dismiss_toast(cx, id, DismissReason::Manual);
+                    }
+                }
+            }
+        }
+        self.start_countdown(cx);
+    }
+    fn start_countdown(&mut self, cx: &mut Cx) {
+        if self.last_time.is_none() && self.toasts.iter().any(|state| state.toast.duration > 0.0) {
+            self.next_frame = cx.new_next_frame();
+        }
+    }
+    /// count down the toasts which are not hovered, `time` is the time of the frame (seconds)
+    fn tick(&mut self, cx: &mut Cx, time: f64) {
+        let dt = self.last_time.map_or(0.0, |last| time - last);
+        let mut expired = Vec::new();
+        for state in self.toasts.iter_mut() {
+            if state.toast.duration > 0.0 && !state.hovered {
+                state.remaining -= dt;
+                if state.remaining <= 0.0 {
+                    expired.push(state.id);
+                }
+            }
+        }
+        for id in expired {
+            self.dismiss_toast(cx, id, DismissReason::Timeout);
+        }
+        if self.toasts.iter().any(|state| state.toast.duration > 0.0) {
+            self.last_time = Some(time);
+            self.next_frame = cx.new_next_frame();
+            self.redraw(cx);
+        } else {
+            self.last_time = None;
+        }
+    }
+    /// ## the history of the toasts, the oldest first
+    /// it can be read from anywhere with `&mut Cx`, e.g. to show a notification panel
+    pub fn history(cx: &mut Cx) -> Vec<NotificationRecord> {
+        let global = cx.global::<NotificationGlobal>().clone();
+        let history = global.history.borrow();
+        history.iter().cloned().collect()
+    }
+    pub fn clear_history(cx: &mut Cx) {
+        let global = cx.global::<NotificationGlobal>().clone();
+        global.history.borrow_mut().clear();
+    }
+    fn update_record<F>(cx: &mut Cx, id: ToastId, f: F)
+    where
+        F: FnOnce(&mut NotificationRecord),
+    {
+        let global = cx.global::<NotificationGlobal>().clone();
+        let mut history = global.history.borrow_mut();
+        if let Some(record) = history.iter_mut().rev().find(|record| record.id == id) {
+            f(record);
+        }
+    }
+    fn push_toast(&mut self, cx: &mut Cx, id: ToastId, toast: Toast) {
+        if self.dedup {
+            if let Some(state) = self
+                .toasts
+                .iter_mut()
+                .chain(self.queue.iter_mut())
+                .find(|state| state.toast.is_same(&toast))
+            {
+                state.merged.push(id);
+                state.remaining = state.toast.duration;
+                let (id, count) = (state.id, state.count());
+                Self::update_record(cx, id, |record| record.count = count);
+                self.redraw(cx);
+                return;
+            }
+        }
+        {
+            let global = cx.global::<NotificationGlobal>().clone();
+            let mut history = global.history.borrow_mut();
+            history.push_back(NotificationRecord {
+                id,
+                toast: toast.clone(),
+                count: 1,
+                created: cx.seconds_since_app_start(),
+                reason: None,
+            });
+            while history.len() > self.history_size {
+                history.pop_front();
+            }
+        }
+        let state = ToastState {
+            id,
+            remaining: toast.duration,
+            toast,
+            hovered: false,
+            merged: Vec::new(),
+        };
+        self.queue.push_back(state);
+        self.show_queued(cx);
+        if self.max_queued > 0 && self.queue.len() > self.max_queued {
+            if let Some(dropped) = self.queue.pop_front() {
+                self.dismissed_all(cx, &dropped, DismissReason::Dropped);
+            }
+        }
+    }
+    /// ## show the waiting toasts while there is a place on the screen and the rate limit allows
+    /// if the rate limit stops it, it is called again when the oldest shown toast leaves the window
+    fn show_queued(&mut self, cx: &mut Cx) {
+        let now = cx.seconds_since_app_start();
+        while let Some(first) = self.shown_times.front() {
+            if now - first < self.rate_interval {
+                break;
+            }
+            self.shown_times.pop_front();
+        }
+        while !self.queue.is_empty()
+            && (self.max_visible == 0 || self.toasts.len() < self.max_visible)
+        {
+            if self.rate_limit > 0 && self.shown_times.len() >= self.rate_limit {
+                if let Some(first) = self.shown_times.front() {
+                    cx.stop_timer(self.rate_timer);
+                    self.rate_timer = cx.start_timeout(first + self.rate_interval - now);
+                }
+                break;
+            }
+            if let Some(next) = self.queue.pop_front() {
+                self.toasts.push(next);
+                if self.rate_limit > 0 {
+                    self.shown_times.push_back(now);
+                }
+            }
+        }
+        self.start_countdown(cx);
+    }
+    fn dismiss_toast(&mut self, cx: &mut Cx, id: ToastId, reason: DismissReason) {
+        // a collapsed id only lowers the counter
+        if let Some(state) = self
+            .toasts
+            .iter_mut()
+            .chain(self.queue.iter_mut())
+            .find(|state| state.merged.contains(&id))
+        {
+            state.merged.retain(|merged| *merged != id);
+            let (first, count) = (state.id, state.count());
+            Self::update_record(cx, first, |record| record.count = count);
+            self.emit(
+                cx,
+                GNotificationEvent::Dismissed(GNotificationDismissedParam { id, reason }),
+            );
+            self.redraw(cx);
+            return;
+        }
+        let state = if let Some(index) = self.toasts.iter().position(|state| state.has_id(id)) {
+            Some(self.toasts.remove(index))
+        } else if let Some(index) = self.queue.iter().position(|state| state.has_id(id)) {
+            self.queue.remove(index)
+        } else {
+            None
+        };
+        let Some(state) = state else {
+            return;
+        };
+        self.views.retain(|view_id, _| view_id.0 != state.id);
+        // a place on the screen is free, show the next waiting toast
+        self.show_queued(cx);
+        self.dismissed_all(cx, &state, reason);
+        self.redraw(cx);
+    }
+    /// record the reason and send `Dismissed` for the toast and every id collapsed into it
+    fn dismissed_all(&mut self, cx: &mut Cx, state: &ToastState, reason: DismissReason) {
+        Self::update_record(cx, state.id, |record| record.reason = Some(reason));
+        for id in std::iter::once(state.id).chain(state.merged.iter().copied()) {
+            self.emit(
+                cx,
+                GNotificationEvent::Dismissed(GNotificationDismissedParam { id, reason }),
+            );
+        }
+    }
+    fn emit(&self, cx: &mut Cx, e: GNotificationEvent) {
+        if !self.event_key {
+            return;
+        }
+        if let Some(path) = self.scope_path.as_ref() {
+            cx.widget_action(self.widget_uid(), path, e);
+        }
+    }
+    fn align(&self) -> Align {
+        match self.position {
+            Position::TopLeft | Position::LeftTop => Align { x: 0.0, y: 0.0 },
+            Position::Top => Align { x: 0.5, y: 0.0 },
+            Position::TopRight | Position::RightTop => Align { x: 1.0, y: 0.0 },
+            Position::Left => Align { x: 0.0, y: 0.5 },
+            Position::Right => Align { x: 1.0, y: 0.5 },
+            Position::BottomLeft | Position::LeftBottom => Align { x: 0.0, y: 1.0 },
+            Position::Bottom | Position::Auto => Align { x: 0.5, y: 1.0 },
+            Position::BottomRight | Position::RightBottom => Align { x: 1.0, y: 1.0 },
+        }
+    }
+}
+
+impl GNotificationRef {
+    ref_event_option! {
+        dismissed => GNotificationDismissedParam,
+        action => GNotificationActionParam
+    }
+    /// the ids of the toasts on the screen
+    pub fn toast_ids(&self) -> Vec<ToastId> {
+        self.borrow().map_or(Vec::new(), |c_ref| {
+            c_ref.toasts.iter().map(|state| state.id).collect()
+        })
+    }
+    /// count of the toasts waiting in the queue
+    pub fn queued(&self) -> usize {
+        self.borrow().map_or(0, |c_ref| c_ref.queue.len())
+    }
 }
 
+impl GNotificationSet {
+    set_event! {
+        dismissed => GNotificationDismissedParam,
+        action => GNotificationActionParam
+    }
+}
@@ -1,6 +1,6 @@
 use makepad_widgets::Cx;
 
-#[allow(unused)]
 pub fn register(cx: &mut Cx) {
     crate::components::notification::live_design(cx);
-}
\ No newline at end of file
+    crate::components::notification::toast::live_design(cx);
+}
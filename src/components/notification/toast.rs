@@ -0,0 +1,277 @@
+use makepad_widgets::*;
+
+use crate::{
+    components::{icon::GIcon, tag::GTag},
+    shader::{draw_text::DrawGText, draw_view::DrawGView, icon_lib::types::IconType},
+    themes::Themes,
+    utils::{get_font_family, ThemeColor},
+};
+
+use super::{event::GToastEvent, types::Toast};
+
+live_design! {
+    link gen_base;
+    use link::gen_theme::*;
+
+    pub GToastBase = {{GToast}}{
+        width: 320.0,
+        height: Fit,
+        flow: Down,
+        font_family: (FONT_FAMILY),
+        content_padding: {left: 12.0, right: 12.0, top: 12.0, bottom: 12.0},
+        draw_title: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+        draw_body: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+    }
+}
+
+/// # A toast of `GNotification`
+/// it is not a widget, `GNotification` draws it for each pushed `Toast`
+#[derive(Live, LiveRegister)]
+pub struct GToast {
+    #[live]
+    pub theme: Themes,
+    #[live]
+    pub background_color: Option<Vec4>,
+    #[live]
+    pub border_color: Option<Vec4>,
+    #[live(1.0)]
+    pub border_width: f32,
+    #[live(6.0)]
+    pub border_radius: f32,
+    #[live]
+    pub shadow_color: Option<Vec4>,
+    #[live(4.8)]
+    pub spread_radius: f32,
+    #[live(4.8)]
+    pub blur_radius: f32,
+    #[live]
+    pub shadow_offset: Vec2,
+    #[live]
+    pub title_color: Option<Vec4>,
+    #[live]
+    pub body_color: Option<Vec4>,
+    #[live]
+    pub progress_color: Option<Vec4>,
+    #[live(10.0)]
+    pub title_font_size: f64,
+    #[live(9.0)]
+    pub body_font_size: f64,
+    #[live(3.0)]
+    pub progress_height: f64,
+    #[live(10.0)]
+    pub spacing: f64,
+    #[live]
+    pub content_padding: Padding,
+    #[live]
+    pub font_family: LiveDependency,
+    #[live]
+    pub draw_toast: DrawGView,
+    #[live]
+    pub draw_title: DrawGText,
+    #[live]
+    pub draw_body: DrawGText,
+    #[live]
+    pub draw_progress: DrawGView,
+    #[walk]
+    pub walk: Walk,
+    #[layout]
+    pub layout: Layout,
+    // slots ---------------------
+    #[live]
+    pub icon: Option<LivePtr>,
+    #[live]
+    pub close: Option<LivePtr>,
+    #[live]
+    pub action: Option<LivePtr>,
+    #[rust]
+    icon_view: Option<GIcon>,
+    #[rust]
+    close_view: Option<GIcon>,
+    #[rust]
+    action_views: ComponentMap<LiveId, GTag>,
+}
+
+impl LiveHook for GToast {
+    fn after_apply(&mut self, _cx: &mut Cx, _apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        self.draw_title.text_style.font_size = self.title_font_size;
+        self.draw_body.text_style.font_size = self.body_font_size;
+        self.draw_title.wrap = TextWrap::Word;
+        self.draw_body.wrap = TextWrap::Word;
+    }
+}
+
+impl GToast {
+    pub fn area(&self) -> Area {
+        self.draw_toast.area()
+    }
+    /// ## draw the toast
+    /// - `count`: how many identical toasts are collapsed into this one, drawn as `×N` after the title
+    /// - `progress`: the remaining part of the duration (`1.0` to `0.0`), `None` hides the progress bar
+    pub fn draw_toast(
+        &mut self,
+        cx: &mut Cx2d,
+        scope: &mut Scope,
+        toast: &Toast,
+        count: usize,
+        progress: Option<f64>,
+    ) {
+        let accent = self.progress_color.get(toast.kind, 500);
+        self.draw_toast.background_color = self.background_color.use_or("#FFFFFF").unwrap();
+        self.draw_toast.background_visible = 1.0;
+        self.draw_toast.border_color = self.border_color.get(toast.kind, 200);
+        self.draw_toast.border_width = self.border_width;
+        self.draw_toast.border_radius = self.border_radius;
+        self.draw_toast.shadow_color = self.shadow_color.use_or("#00000026").unwrap();
+        self.draw_toast.spread_radius = self.spread_radius;
+        self.draw_toast.blur_radius = self.blur_radius;
+        self.draw_toast.shadow_offset = self.shadow_offset;
+        self.draw_title.color = self.title_color.use_or("#101828").unwrap();
+        self.draw_body.color = self.body_color.use_or("#475467").unwrap();
+
+        let _ = self.draw_toast.begin(cx, self.walk, self.layout);
+        // the close icon is placed at the top right corner, its space is kept by the padding
+        let mut close_walk = self
+            .close_view
+            .get_or_insert_with(|| GIcon::new_from_ptr(cx, self.close))
+            .walk(cx);
+        let close_width = match close_walk.width {
+            Size::Fixed(width) => width,
+            _ => 0.0,
+        };
+        let rect = cx.turtle().rect();
+        close_walk.abs_pos = Some(dvec2(
+            rect.pos.x + rect.size.x - self.content_padding.right - close_width,
+            rect.pos.y + self.content_padding.top,
+        ));
+        let mut padding = self.content_padding;
+        padding.right += close_width + self.spacing;
+        cx.begin_turtle(
+            Walk::fill_fit(),
+            Layout {
+                flow: Flow::Right,
+                spacing: self.spacing,
+                padding,
+                ..Default::default()
+            },
+        );
+        if let Some(icon_type) = toast.icon.as_ref() {
+            // the toast of a view never changes, so the icon is set once
+            let icon = self.icon_view.get_or_insert_with(|| {
+                let mut icon = GIcon::new_from_ptr(cx, self.icon);
+                set_icon(cx, &mut icon, icon_type.clone(), accent);
+                icon
+            });
+            let walk = icon.walk(cx);
+            let _ = icon.draw_walk(cx, scope, walk);
+        }
+        // title, body and actions
+        cx.begin_turtle(
+            Walk::fill_fit(),
+            Layout {
+                flow: Flow::Down,
+                spacing: self.spacing / 2.0,
+                ..Default::default()
+            },
+        );
+        let _ = get_font_family(&self.font_family, cx, &mut self.draw_title.text_style.font);
+        let _ = get_font_family(&self.font_family, cx, &mut self.draw_body.text_style.font);
+        if count > 1 {
+            let title = format!("{} ×{}", toast.title, count);
+            self.draw_title
+                .draw_walk(cx, Walk::fill_fit(), Align::default(), &title);
+        } else {
+            self.draw_title
+                .draw_walk(cx, Walk::fill_fit(), Align::default(), &toast.title);
+        }
+        if !toast.body.is_empty() {
+            self.draw_body
+                .draw_walk(cx, Walk::fill_fit(), Align::default(), &toast.body);
+        }
+        if !toast.actions.is_empty() {
+            cx.begin_turtle(
+                Walk::fill_fit(),
+                Layout {
+                    flow: Flow::RightWrap,
+                    spacing: self.spacing / 2.0,
+                    ..Default::default()
+                },
+            );
+            for (index, text) in toast.actions.iter().enumerate() {
+                let tag = self
+                    .action_views
+                    .get_or_insert(cx, LiveId(index as u64), |cx| GTag::new_from_ptr(cx, self.action));
+                if tag.text.as_ref() != text.as_str() {
+                    tag.text.as_mut_empty().push_str(text);
+                }
+                let walk = tag.walk(cx);
+                let _ = tag.draw_walk(cx, scope, walk);
+            }
+            cx.end_turtle();
+        }
+        cx.end_turtle();
+        cx.end_turtle();
+        if let Some(close) = self.close_view.as_mut() {
+            let _ = close.draw_walk(cx, scope, close_walk);
+        }
+
+        if let Some(progress) = progress {
+            self.draw_progress.background_color = accent;
+            self.draw_progress.background_visible = 1.0;
+            self.draw_progress.border_radius = (self.progress_height / 2.0) as f32;
+            let width = cx.turtle().rect().size.x * progress.clamp(0.0, 1.0);
+            self.draw_progress.draw_walk(
+                cx,
+                Walk::size(Size::Fixed(width), Size::Fixed(self.progress_height)),
+            );
+        }
+        let _ = self.draw_toast.end(cx);
+    }
+    pub fn handle_event_with(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        scope: &mut Scope,
+        dispatch_action: &mut dyn FnMut(&mut Cx, GToastEvent),
+    ) {
+        if let Some(close) = self.close_view.as_mut() {
+            let actions = cx.capture_actions(|cx| close.handle_event(cx, event, scope));
+            if close.clicked(&actions).is_some() {
+                dispatch_action(cx, GToastEvent::Closed);
+                return;
+            }
+        }
+        let mut clicked = None;
+        for (id, tag) in self.action_views.iter_mut() {
+            let actions = cx.capture_actions(|cx| tag.handle_event(cx, event, scope));
+            if tag.clicked(&actions).is_some() {
+                clicked = Some(id.0 as usize);
+            }
+        }
+        if let Some(index) = clicked {
+            dispatch_action(cx, GToastEvent::Action(index));
+        }
+    }
+}
+
+/// set the icon type and color, the draw type of the icon follows the icon type
+fn set_icon(cx: &mut Cx, icon: &mut GIcon, icon_type: IconType, color: Vec4) {
+    match icon_type.to_draw_type() {
+        Ok(ty) => {
+            icon.draw_type.replace(ty);
+            icon.icon_type = icon_type;
+            icon.color.replace(color);
+            if let Err(e) = icon.render(cx) {
+                error!("GToast icon render error: {:?}", e);
+            }
+        }
+        Err(e) => error!("GToast icon error: {:?}", e),
+    }
+}
@@ -0,0 +1,115 @@
+use std::mem::discriminant;
+
+use crate::{shader::icon_lib::types::IconType, themes::Themes};
+
+pub type ToastId = u64;
+
+/// ## A toast pushed to `GNotification`
+/// ```rust
+/// GNotification::push(
+///     cx,
+///     Toast::new("Saved")
+///         .with_body("The file is saved")
+///         .with_kind(Themes::Success)
+///         .with_action("Undo"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub title: String,
+    pub body: String,
+    /// the accent color of the toast (icon, border and progress bar)
+    pub kind: Themes,
+    pub icon: Option<IconType>,
+    /// seconds before the toast is dismissed, `0.0` keeps it until it is closed
+    pub duration: f64,
+    /// texts of the action buttons
+    pub actions: Vec<String>,
+}
+
+impl Default for Toast {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            body: String::new(),
+            kind: Themes::Info,
+            icon: None,
+            duration: 4.5,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl Toast {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = body.to_string();
+        self
+    }
+    pub fn with_kind(mut self, kind: Themes) -> Self {
+        self.kind = kind;
+        self
+    }
+    pub fn with_icon(mut self, icon: IconType) -> Self {
+        self.icon.replace(icon);
+        self
+    }
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+    pub fn with_action(mut self, text: &str) -> Self {
+        self.actions.push(text.to_string());
+        self
+    }
+    /// identical toasts are collapsed into one with a counter
+    pub fn is_same(&self, other: &Toast) -> bool {
+        self.title == other.title
+            && self.body == other.body
+            && discriminant(&self.kind) == discriminant(&other.kind)
+            && self.icon.as_ref().map(discriminant) == other.icon.as_ref().map(discriminant)
+            && self.duration == other.duration
+            && self.actions == other.actions
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DismissReason {
+    /// the duration is over
+    Timeout,
+    /// the close button is clicked
+    Closed,
+    /// an action button is clicked
+    Action,
+    /// `GNotification::dismiss` or `GNotification::clear` is called
+    Manual,
+    /// the queue is full, the oldest waiting toast is dropped without being shown
+    Dropped,
+}
+
+/// ## A toast in the notification history
+/// see `GNotification::history`
+#[derive(Debug, Clone)]
+pub struct NotificationRecord {
+    pub id: ToastId,
+    pub toast: Toast,
+    /// how many identical toasts are collapsed into this one
+    pub count: usize,
+    /// seconds since the app start (`Cx::seconds_since_app_start`)
+    pub created: f64,
+    /// `None` if the toast is still on the screen (or waiting in the queue)
+    pub reason: Option<DismissReason>,
+}
+
+/// commands sent from anywhere to the notification center
+#[derive(Debug, Clone)]
+pub(crate) enum NotificationCommand {
+    Push(ToastId, Toast),
+    Dismiss(ToastId),
+    Clear,
+}
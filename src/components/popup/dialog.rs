@@ -0,0 +1,439 @@
+use makepad_widgets::*;
+
+use crate::{
+    components::{button::GButton, label::GLabelWidgetRefExt, view::GView},
+    event_option, ref_event_option, set_event, set_scope_path,
+    shader::{draw_text::DrawGText, draw_view::DrawGView},
+    utils::{get_font_family, ThemeColor},
+};
+
+use super::{
+    event::{GDialogClosedParam, GDialogEvent},
+    stack::PopupStackGlobal,
+    types::{DialogButton, DialogResult},
+};
+
+live_design! {
+    link gen_base;
+    use link::gen_theme::*;
+
+    pub GDialogBoxBase = {{GDialogBox}}{
+        width: 420.0,
+        height: Fit,
+        flow: Down,
+        spacing: 16.0,
+        padding: {left: 24.0, right: 24.0, top: 20.0, bottom: 20.0},
+        font_family: (FONT_FAMILY),
+        draw_title: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+    }
+}
+
+/// # GDialogBox component
+/// A modal dialog, it is drawn on top of everything with a backdrop which blocks the clicks below.
+///
+/// Put one `GDialogBox` in the page, it takes no space until it is opened.
+/// ```rust
+/// self.gdialog_box(id!(dialog)).open_with(
+///     cx,
+///     "Delete the file?",
+///     None,
+///     vec![DialogButton::cancel("Cancel"), DialogButton::confirm("Delete")],
+/// );
+/// // ...
+/// if let Some(param) = self.gdialog_box(id!(dialog)).closed(&actions) {
+///     if param.result == DialogResult::Confirmed { /* ... */ }
+/// }
+/// ```
+/// - `Tab` / `Shift + Tab` move the focus through the focusable widgets of the content (the widgets
+///   with a nav stop, e.g. `GInput`, `GSelect`) and then the buttons, the focus never leaves the dialog
+/// - `Enter` presses the focused button, `Esc` closes the dialog with `Cancelled` (see `close_on_esc`)
+///
+/// Note: the `GDialog` in DSL is the dialog mode of `GPopup` (used by `GDropDown`), not this widget.
+///
+/// ## Event
+/// - `Closed(GDialogClosedParam)`: the dialog is closed, with the result
+#[derive(Live, Widget)]
+pub struct GDialogBox {
+    #[live]
+    pub title: ArcStringMut,
+    /// the template of the content, a `GView`
+    #[live]
+    pub content: Option<LivePtr>,
+    /// the template of the buttons
+    #[live]
+    pub button: Option<LivePtr>,
+    /// the template of the primary buttons
+    #[live]
+    pub primary_button: Option<LivePtr>,
+    #[live]
+    pub backdrop_color: Option<Vec4>,
+    #[live]
+    pub background_color: Option<Vec4>,
+    #[live]
+    pub title_color: Option<Vec4>,
+    #[live(8.0)]
+    pub border_radius: f32,
+    #[live]
+    pub shadow_color: Option<Vec4>,
+    #[live(4.8)]
+    pub spread_radius: f32,
+    #[live(12.0)]
+    pub blur_radius: f32,
+    #[live]
+    pub shadow_offset: Vec2,
+    #[live(12.0)]
+    pub title_font_size: f64,
+    #[live]
+    pub font_family: LiveDependency,
+    /// close with `Cancelled` when `Esc` is pressed
+    #[live(true)]
+    pub close_on_esc: bool,
+    /// close with `Cancelled` when the backdrop is clicked
+    #[live(false)]
+    pub close_on_backdrop: bool,
+    #[live(false)]
+    pub opened: bool,
+    #[live]
+    pub draw_backdrop: DrawGView,
+    #[live]
+    pub draw_dialog: DrawGView,
+    #[live]
+    pub draw_title: DrawGText,
+    #[redraw]
+    #[live]
+    pub draw_list: DrawList2d,
+    #[walk]
+    pub walk: Walk,
+    #[layout]
+    pub layout: Layout,
+    #[live(true)]
+    pub visible: bool,
+    #[live(true)]
+    pub event_key: bool,
+    #[rust]
+    pub buttons: Vec<DialogButton>,
+    #[rust]
+    content_view: Option<GView>,
+    #[rust]
+    button_views: ComponentMap<LiveId, GButton>,
+    /// the button focused when the dialog is opened
+    #[rust]
+    focus_index: Option<usize>,
+    /// the nav stops of the content in draw order, collected in each draw
+    #[rust]
+    content_stops: Vec<Area>,
+    /// the backdrop is locked after it is drawn
+    #[rust]
+    lock_pending: bool,
+    #[rust]
+    pub scope_path: Option<HeapLiveIdPath>,
+}
+
+impl LiveHook for GDialogBox {
+    fn after_apply(&mut self, _cx: &mut Cx, _apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        self.draw_title.text_style.font_size = self.title_font_size;
+        self.draw_title.wrap = TextWrap::Word;
+    }
+    fn after_new_from_doc(&mut self, _cx: &mut Cx) {
+        if self.buttons.is_empty() {
+            self.buttons = vec![DialogButton::cancel("Cancel"), DialogButton::confirm("OK")];
+        }
+        self.lock_pending = self.opened;
+    }
+}
+
+impl Widget for GDialogBox {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, _walk: Walk) -> DrawStep {
+        if !self.visible || !self.opened {
+            return DrawStep::done();
+        }
+        self.set_scope_path(&scope.path);
+        self.draw_backdrop.background_color = self.backdrop_color.use_or("#00000066").unwrap();
+        self.draw_backdrop.background_visible = 1.0;
+        self.draw_dialog.background_color = self.background_color.use_or("#FFFFFF").unwrap();
+        self.draw_dialog.background_visible = 1.0;
+        self.draw_dialog.border_radius = self.border_radius;
+        self.draw_dialog.shadow_color = self.shadow_color.use_or("#00000033").unwrap();
+        self.draw_dialog.spread_radius = self.spread_radius;
+        self.draw_dialog.blur_radius = self.blur_radius;
+        self.draw_dialog.shadow_offset = self.shadow_offset;
+        self.draw_title.color = self.title_color.use_or("#101828").unwrap();
+
+        if PopupStackGlobal::take_raise(cx, self.widget_uid()) {
+            self.draw_list.begin_overlay_last(cx);
+        } else {
+            self.draw_list.begin_overlay_reuse(cx);
+        }
+        cx.begin_pass_sized_turtle(Layout::flow_down());
+        let _ = self.draw_backdrop.begin(
+            cx,
+            Walk::fill(),
+            Layout {
+                align: Align { x: 0.5, y: 0.5 },
+                ..Default::default()
+            },
+        );
+        let _ = self.draw_dialog.begin(cx, self.walk, self.layout);
+        if !self.title.as_ref().is_empty() {
+            let _ = get_font_family(&self.font_family, cx, &mut self.draw_title.text_style.font);
+            self.draw_title
+                .draw_walk(cx, Walk::fill_fit(), Align::default(), self.title.as_ref());
+        }
+        if let Some(content) = self.content_view.as_mut() {
+            let walk = content.walk(cx);
+            let _ = content.draw_walk(cx, scope, walk);
+        }
+        cx.begin_turtle(
+            Walk::fill_fit(),
+            Layout {
+                flow: Flow::Right,
+                spacing: self.layout.spacing / 2.0,
+                align: Align { x: 1.0, y: 0.5 },
+                ..Default::default()
+            },
+        );
+        for (index, button) in self.buttons.iter().enumerate() {
+            let ptr = if button.primary {
+                self.primary_button
+            } else {
+                self.button
+            };
+            let view = self
+                .button_views
+                .get_or_insert(cx, LiveId(index as u64), |cx| {
+                    let view = GButton::new_from_ptr(cx, ptr);
+                    let _ = view.slot.as_glabel().set_text(cx, button.text.clone());
+                    view
+                });
+            let walk = view.walk(cx);
+            let _ = view.draw_walk(cx, scope, walk);
+        }
+        cx.end_turtle();
+        let _ = self.draw_dialog.end(cx);
+        let _ = self.draw_backdrop.end(cx);
+        cx.end_pass_sized_turtle_with_shift(Area::Empty, DVec2::default());
+        self.draw_list.end(cx);
+
+        let mut content_stops = Vec::new();
+        Cx2d::iterate_nav_stops(cx, self.draw_list.id(), |_, stop| {
+            content_stops.push(stop.area);
+            None
+        });
+        self.content_stops = content_stops;
+
+        if self.lock_pending {
+            self.lock_pending = false;
+            PopupStackGlobal::open(cx, self.widget_uid(), self.draw_backdrop.area());
+            self.draw_list.redraw(cx);
+            self.focus_button(cx, self.focus_index);
+        }
+        PopupStackGlobal::set_popup_area(cx, self.widget_uid(), self.draw_dialog.area());
+        DrawStep::done()
+    }
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.visible || !self.opened {
+            return;
+        }
+        let sweep_area = self.draw_backdrop.area();
+        // the keys are handled only when the dialog is the top popup
+        let accepts = PopupStackGlobal::accepts(cx, self.widget_uid(), event);
+        if let (Event::KeyDown(e), true) = (event, accepts) {
+            match e.key_code {
+                KeyCode::Escape if self.close_on_esc => {
+                    self.close(cx, DialogResult::Cancelled);
+                    return;
+                }
+                KeyCode::Tab => {
+                    self.move_focus(cx, e.modifiers.shift);
+                    return;
+                }
+                KeyCode::ReturnKey => {
+                    let pressed = self.button_views.iter().find_map(|(id, view)| {
+                        cx.has_key_focus(view.area()).then_some(id.0 as usize)
+                    });
+                    if let Some(result) = pressed.and_then(|index| self.buttons.get(index)) {
+                        self.close(cx, result.result);
+                        return;
+                    }
+                }
+                _ => (),
+            }
+        }
+        if let Some(content) = self.content_view.as_mut() {
+            content.handle_event_with(cx, event, scope, sweep_area);
+        }
+        let mut clicked = None;
+        for (id, view) in self.button_views.iter_mut() {
+            let actions = cx.capture_actions(|cx| view.handle_event_with(cx, event, scope, sweep_area));
+            if view.clicked(&actions).is_some() {
+                clicked = Some(id.0 as usize);
+            }
+        }
+        if let Some(button) = clicked.and_then(|index| self.buttons.get(index)) {
+            self.close(cx, button.result);
+            return;
+        }
+        if !accepts {
+            return;
+        }
+        // the backdrop takes every hit which is not taken by the dialog
+        if let Hit::FingerUp(e) = event.hits_with_options(
+            cx,
+            sweep_area,
+            HitOptions::new().with_sweep_area(sweep_area),
+        ) {
+            if self.close_on_backdrop
+                && e.is_over
+                && !self.draw_dialog.area().clipped_rect(cx).contains(e.abs)
+            {
+                self.close(cx, DialogResult::Cancelled);
+            }
+        }
+    }
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl GDialogBox {
+    set_scope_path!();
+    event_option! {
+        closed: GDialogEvent::Closed => GDialogClosedParam
+    }
+    pub fn area(&self) -> Area {
+        self.draw_dialog.area()
+    }
+    /// open the dialog with the current title, content and buttons
+    pub fn open(&mut self, cx: &mut Cx) {
+        if self.opened {
+            return;
+        }
+        if self.content_view.is_none() && self.content.is_some() {
+            self.content_view = Some(GView::new_from_ptr(cx, self.content));
+        }
+        self.opened = true;
+        self.lock_pending = true;
+        self.focus_index = self
+            .buttons
+            .iter()
+            .position(|button| button.primary)
+            .or_else(|| self.buttons.len().checked_sub(1));
+        self.redraw(cx);
+    }
+    /// ## open the dialog with a title, a content template (`None` keeps the current content) and buttons
+    /// an empty `buttons` keeps the current buttons
+    pub fn open_with(
+        &mut self,
+        cx: &mut Cx,
+        title: &str,
+        content: Option<LivePtr>,
+        buttons: Vec<DialogButton>,
+    ) {
+        self.title.as_mut_empty().push_str(title);
+        if content.is_some() && content != self.content {
+            self.content = content;
+            self.content_view = None;
+        }
+        if !buttons.is_empty() {
+            self.buttons = buttons;
+            self.button_views.clear();
+        }
+        self.open(cx);
+    }
+    /// close the dialog, `Closed` is sent with the result
+    pub fn close(&mut self, cx: &mut Cx, result: DialogResult) {
+        if !self.opened {
+            return;
+        }
+        self.opened = false;
+        self.lock_pending = false;
+        PopupStackGlobal::close(cx, self.widget_uid());
+        self.emit(cx, GDialogEvent::Closed(GDialogClosedParam { result }));
+        self.draw_list.redraw(cx);
+    }
+    /// the content view, it is created when the dialog is opened
+    pub fn content(&mut self) -> Option<&mut GView> {
+        self.content_view.as_mut()
+    }
+    /// the focusable areas in `Tab` order: the nav stops of the content, then the buttons
+    fn focus_ring(&self) -> Vec<Area> {
+        let mut ring = self.content_stops.clone();
+        ring.extend(
+            (0..self.buttons.len())
+                .filter_map(|index| self.button_views.get(&LiveId(index as u64)))
+                .map(|view| view.area()),
+        );
+        ring
+    }
+    /// move the focus to the next (or previous) focusable area, it wraps around
+    fn move_focus(&mut self, cx: &mut Cx, back: bool) {
+        let ring = self.focus_ring();
+        let len = ring.len();
+        if len == 0 {
+            return;
+        }
+        let current = ring.iter().position(|area| cx.has_key_focus(*area));
+        let index = match current {
+            Some(index) if back => (index + len - 1) % len,
+            Some(index) => (index + 1) % len,
+            None if back => len - 1,
+            None => 0,
+        };
+        self.focus_index = index.checked_sub(self.content_stops.len());
+        cx.set_key_focus(ring[index]);
+    }
+    fn focus_button(&mut self, cx: &mut Cx, index: Option<usize>) {
+        self.focus_index = index;
+        if let Some(view) = index.and_then(|index| self.button_views.get(&LiveId(index as u64))) {
+            cx.set_key_focus(view.area());
+        }
+    }
+    fn emit(&self, cx: &mut Cx, e: GDialogEvent) {
+        if !self.event_key {
+            return;
+        }
+        if let Some(path) = self.scope_path.as_ref() {
+            cx.widget_action(self.widget_uid(), path, e);
+        }
+    }
+}
+
+impl GDialogBoxRef {
+    ref_event_option! {
+        closed => GDialogClosedParam
+    }
+    pub fn open(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.open(cx);
+        }
+    }
+    pub fn open_with(
+        &self,
+        cx: &mut Cx,
+        title: &str,
+        content: Option<LivePtr>,
+        buttons: Vec<DialogButton>,
+    ) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.open_with(cx, title, content, buttons);
+        }
+    }
+    pub fn close(&self, cx: &mut Cx, result: DialogResult) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.close(cx, result);
+        }
+    }
+    pub fn is_opened(&self) -> bool {
+        self.borrow().map_or(false, |c_ref| c_ref.opened)
+    }
+}
+
+impl GDialogBoxSet {
+    set_event! {
+        closed => GDialogClosedParam
+    }
+}
@@ -0,0 +1,14 @@
+use makepad_widgets::{ActionDefaultRef, DefaultNone};
+
+use super::types::DialogResult;
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GDialogEvent {
+    Closed(GDialogClosedParam),
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct GDialogClosedParam {
+    pub result: DialogResult,
+}
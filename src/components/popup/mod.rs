@@ -1,4 +1,13 @@
+pub mod dialog;
+mod event;
 pub mod register;
+mod stack;
+mod types;
+
+pub use dialog::*;
+pub use event::*;
+pub use stack::{PopupLayer, PopupStackGlobal};
+pub use types::{DialogButton, DialogResult};
 
 use makepad_widgets::*;
 
@@ -118,6 +127,9 @@ pub struct GPopup {
     draw_list: DrawList2d,
     #[rust]
     pub container_walk: Option<Walk>,
+    /// draw the popup as the last overlay in next `begin`, so it is above all other popups
+    #[rust]
+    pub raise: bool,
 }
 
 impl LiveHook for GPopup {
@@ -194,7 +206,12 @@ impl GPopup {
     /// ## Begin to draw popup
     /// this method is used to begin drawing the popup
     pub fn begin(&mut self, cx: &mut Cx2d) {
-        self.draw_list.begin_overlay_reuse(cx);
+        if self.raise {
+            self.raise = false;
+            self.draw_list.begin_overlay_last(cx);
+        } else {
+            self.draw_list.begin_overlay_reuse(cx);
+        }
         cx.begin_pass_sized_turtle(Layout::flow_down());
         self.draw_popup.begin(cx, self.walk, self.layout);
     }
@@ -238,6 +255,32 @@ impl GPopup {
         position: Position,
         proportion: f32,
         redraw: &mut bool,
+    ) {
+        let w = Walk {
+            height: Size::All,
+            width: Size::All,
+            ..Default::default()
+        };
+        let popup_size = cx.peek_walk_turtle(w).size;
+        let full = match position.to_drawer() {
+            Position::Left | Position::Right => popup_size.x,
+            _ => popup_size.y,
+        };
+        let size = if proportion > 1.0 {
+            proportion as f64
+        } else {
+            proportion as f64 * full
+        };
+        self.draw_container_drawer_sized(cx, scope, position, size, redraw);
+    }
+    /// ## draw the drawer with a fixed size (width for `Left`/`Right`, height for `Top`/`Bottom`)
+    pub fn draw_container_drawer_sized(
+        &mut self,
+        cx: &mut Cx2d,
+        scope: &mut Scope,
+        position: Position,
+        size: f64,
+        redraw: &mut bool,
     ) {
         self.draw_popup.position = position;
         let w = Walk {
@@ -247,53 +290,11 @@ impl GPopup {
         };
         let popup_size = cx.peek_walk_turtle(w).size;
         // now get virtual box as rect
-        let (adjust_size, adjust_pos) = match position {
-            Position::Left | Position::LeftTop | Position::LeftBottom => {
-                let x = if proportion > 1.0 {
-                    proportion as f64
-                } else {
-                    proportion as f64 * popup_size.x
-                };
-                let size = DVec2 { x, y: popup_size.y };
-                let pos = DVec2 { x: 0.0, y: 0.0 };
-                (size, pos)
-            }
-            Position::Right | Position::RightTop | Position::RightBottom => {
-                let x = if proportion > 1.0 {
-                    proportion as f64
-                } else {
-                    proportion as f64 * popup_size.x
-                };
-                let size = DVec2 { x, y: popup_size.y };
-                let pos = DVec2 {
-                    x: (1.0 - proportion) as f64 * popup_size.x,
-                    y: 0.0,
-                };
-                (size, pos)
-            }
-            Position::Top | Position::TopLeft | Position::TopRight => {
-                let y = if proportion > 1.0 {
-                    proportion as f64
-                } else {
-                    proportion as f64 * popup_size.y
-                };
-                let size = DVec2 { x: popup_size.x, y };
-                let pos = DVec2 { x: 0.0, y: 0.0 };
-                (size, pos)
-            }
-            Position::Bottom | Position::BottomLeft | Position::BottomRight => {
-                let y = if proportion > 1.0 {
-                    proportion as f64
-                } else {
-                    proportion as f64 * popup_size.y
-                };
-                let size = DVec2 { x: popup_size.x, y };
-                let pos = DVec2 {
-                    x: 0.0,
-                    y: (1.0 - proportion) as f64 * popup_size.y,
-                };
-                (size, pos)
-            }
+        let (adjust_size, adjust_pos) = match position.to_drawer() {
+            Position::Left => (dvec2(size, popup_size.y), DVec2::default()),
+            Position::Right => (dvec2(size, popup_size.y), dvec2(popup_size.x - size, 0.0)),
+            Position::Top => (dvec2(popup_size.x, size), DVec2::default()),
+            _ => (dvec2(popup_size.x, size), dvec2(0.0, popup_size.y - size)),
         };
 
         self.container_walk.replace(Walk {
@@ -2,4 +2,5 @@ use makepad_widgets::Cx;
 
 pub fn register(cx: &mut Cx) {
     crate::components::popup::live_design(cx);
+    crate::components::popup::dialog::live_design(cx);
 }
\ No newline at end of file
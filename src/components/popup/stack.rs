@@ -0,0 +1,153 @@
+use std::{cell::RefCell, rc::Rc};
+
+use makepad_widgets::*;
+
+/// a layer of the popup stack, one for each opened popup (dropdown, dialog, drawer, tooltip)
+#[derive(Debug, Clone)]
+pub struct PopupLayer {
+    /// the widget which owns the popup
+    pub uid: WidgetUid,
+    /// the area which is sweep locked while the layer is on the top,
+    /// `Area::Empty` means the layer does not lock (e.g. a suggestion list which keeps its input usable),
+    /// the lock of the layer below is kept
+    pub lock_area: Area,
+    /// the area of the popup itself, set after the popup is drawn
+    pub popup_area: Area,
+    /// the popup should be moved to the top of the overlays in the next draw
+    raise: bool,
+}
+
+/// # Popup stack
+/// All opened popups are kept in a global stack, the last opened one is on the top:
+/// - the top layer is drawn above the others (see `GPopup::raise`)
+/// - only the topmost locking layer holds the sweep lock, the lock of the layer below is back when it is closed
+/// - a click outside closes only the top layer (`CloseMode::Out`), clicks in an upper layer never close the lower ones
+/// - events go to the topmost layer first: the widget tree still dispatches every event, but a layer
+///   handles an event itself only if `accepts` allows it, so keys go only to the top layer and pointer
+///   events over an upper layer are left to that layer. The children of a layer (e.g. a dropdown in a dialog)
+///   always get the event, they check it for their own layer.
+#[derive(Default, Clone)]
+pub struct PopupStackGlobal {
+    layers: Rc<RefCell<Vec<PopupLayer>>>,
+    /// time of the mouse down which has closed a layer, the same mouse down can not close another one
+    closed_at: Rc<RefCell<Option<f64>>>,
+}
+
+impl PopupStackGlobal {
+    /// push (or move) the layer of `uid` to the top and lock the `lock_area` (if it is not `Area::Empty`)
+    pub fn open(cx: &mut Cx, uid: WidgetUid, lock_area: Area) {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let mut layers = global.layers.borrow_mut();
+        if let Some(index) = layers.iter().position(|layer| layer.uid == uid) {
+            Self::remove_layer(cx, &mut layers, index);
+        }
+        if !lock_area.is_empty() {
+            if let Some(holder) = Self::lock_holder(&layers) {
+                cx.sweep_unlock(holder.lock_area);
+            }
+            cx.sweep_lock(lock_area);
+        }
+        layers.push(PopupLayer {
+            uid,
+            lock_area,
+            popup_area: Area::Empty,
+            raise: true,
+        });
+    }
+    /// remove the layer of `uid`, if it held the lock, the next locking layer below gets the lock back
+    pub fn close(cx: &mut Cx, uid: WidgetUid) {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let mut layers = global.layers.borrow_mut();
+        if let Some(index) = layers.iter().position(|layer| layer.uid == uid) {
+            Self::remove_layer(cx, &mut layers, index);
+        }
+    }
+    /// the topmost layer which locks, it holds the sweep lock
+    fn lock_holder(layers: &[PopupLayer]) -> Option<&PopupLayer> {
+        layers.iter().rev().find(|layer| !layer.lock_area.is_empty())
+    }
+    fn remove_layer(cx: &mut Cx, layers: &mut Vec<PopupLayer>, index: usize) {
+        let was_holder = Self::lock_holder(layers).map_or(false, |holder| holder.uid == layers[index].uid);
+        let layer = layers.remove(index);
+        if was_holder {
+            cx.sweep_unlock(layer.lock_area);
+            if let Some(holder) = Self::lock_holder(layers) {
+                cx.sweep_lock(holder.lock_area);
+            }
+        }
+    }
+    /// ## close the layer of `uid` by a mouse down outside of it
+    /// back `true` if the layer should be closed: it is the top and no layer has been closed by the same mouse down
+    pub fn close_out(cx: &mut Cx, uid: WidgetUid, time: f64) -> bool {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        if *global.closed_at.borrow() == Some(time) || !Self::is_top(cx, uid) {
+            return false;
+        }
+        global.closed_at.replace(Some(time));
+        true
+    }
+    pub fn is_top(cx: &mut Cx, uid: WidgetUid) -> bool {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let layers = global.layers.borrow();
+        layers.last().map_or(false, |layer| layer.uid == uid)
+    }
+    /// the depth of the layer, `0` is the bottom
+    pub fn depth(cx: &mut Cx, uid: WidgetUid) -> Option<usize> {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let layers = global.layers.borrow();
+        layers.iter().position(|layer| layer.uid == uid)
+    }
+    /// count of the opened popups
+    pub fn len(cx: &mut Cx) -> usize {
+        cx.global::<PopupStackGlobal>().layers.borrow().len()
+    }
+    /// set the area of the popup after it is drawn, it is used to check if a position is over the layer
+    pub fn set_popup_area(cx: &mut Cx, uid: WidgetUid, area: Area) {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let mut layers = global.layers.borrow_mut();
+        if let Some(layer) = layers.iter_mut().find(|layer| layer.uid == uid) {
+            layer.popup_area = area;
+        }
+    }
+    /// is the position over a layer above the layer of `uid`
+    pub fn is_over_above(cx: &mut Cx, uid: WidgetUid, pos: DVec2) -> bool {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let layers = global.layers.borrow();
+        let Some(index) = layers.iter().position(|layer| layer.uid == uid) else {
+            return false;
+        };
+        layers[index + 1..]
+            .iter()
+            .any(|layer| layer.popup_area.clipped_rect(cx).contains(pos))
+    }
+    /// ## should the layer of `uid` handle the event itself
+    /// - key and text events: only the top layer
+    /// - pointer events: only if the position is not over a layer above
+    /// - other events, or `uid` is not in the stack: always
+    pub fn accepts(cx: &mut Cx, uid: WidgetUid, event: &Event) -> bool {
+        if Self::depth(cx, uid).is_none() {
+            return true;
+        }
+        match event {
+            Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::TextInput(_)
+            | Event::TextCopy(_)
+            | Event::TextCut(_) => Self::is_top(cx, uid),
+            Event::MouseDown(e) => !Self::is_over_above(cx, uid, e.abs),
+            Event::MouseMove(e) => !Self::is_over_above(cx, uid, e.abs),
+            Event::MouseUp(e) => !Self::is_over_above(cx, uid, e.abs),
+            Event::Scroll(e) => !Self::is_over_above(cx, uid, e.abs),
+            _ => true,
+        }
+    }
+    /// back `true` once after the layer of `uid` is opened, the popup should be drawn as the last overlay
+    pub fn take_raise(cx: &mut Cx, uid: WidgetUid) -> bool {
+        let global = cx.global::<PopupStackGlobal>().clone();
+        let mut layers = global.layers.borrow_mut();
+        layers
+            .iter_mut()
+            .find(|layer| layer.uid == uid)
+            .map_or(false, |layer| std::mem::take(&mut layer.raise))
+    }
+}
@@ -0,0 +1,46 @@
+use makepad_widgets::LiveId;
+
+/// the result of a `GDialogBox`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DialogResult {
+    Confirmed,
+    Cancelled,
+    Custom(LiveId),
+}
+
+/// a button in the footer of a `GDialogBox`
+#[derive(Clone, Debug)]
+pub struct DialogButton {
+    pub text: String,
+    pub result: DialogResult,
+    /// use the `primary_button` template, the primary button gets the focus when the dialog opens
+    pub primary: bool,
+}
+
+impl DialogButton {
+    pub fn confirm(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            result: DialogResult::Confirmed,
+            primary: true,
+        }
+    }
+    pub fn cancel(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            result: DialogResult::Cancelled,
+            primary: false,
+        }
+    }
+    pub fn custom(text: &str, id: LiveId) -> Self {
+        Self {
+            text: text.to_string(),
+            result: DialogResult::Custom(id),
+            primary: false,
+        }
+    }
+    pub fn with_primary(mut self, primary: bool) -> Self {
+        self.primary = primary;
+        self
+    }
+}
@@ -30,6 +30,7 @@ pub fn register(cx: &mut Cx){
     crate::components::tabbar::register::register(cx);
     crate::components::router::register::register(cx);
     crate::components::menu::register::register(cx);
+    crate::components::notification::register::register(cx);
 
     crate::components::tab::register::register(cx);
     crate::components::tab::live_design(cx);
@@ -26,6 +26,8 @@ pub struct GSelectOptionsChangedParam {
     pub text: String,
     pub value: String,
     pub selected_id: usize,
+    /// all selected values (in selection order), it is filled by `GSelect`
+    pub values: Vec<String>,
     pub e: FingerUpEvent,
 }
 
@@ -33,6 +35,23 @@ pub struct GSelectOptionsChangedParam {
 #[allow(unused)]
 #[derive(Debug, Clone, DefaultNone)]
 pub enum GSelectEvent {
+    /// an option is clicked
     Changed(GSelectOptionsChangedParam),
+    /// the selection is changed without a click: keyboard, tag close or `remove_option`
+    ValuesChanged(GSelectValuesChangedParam),
     None,
 }
+
+#[derive(Clone, Debug)]
+pub struct GSelectValuesChangedParam {
+    /// `true` if the option is selected, `false` if it is unselected (only in multiple mode)
+    pub selected: bool,
+    /// text of the changed option
+    pub text: String,
+    /// value of the changed option
+    pub value: String,
+    /// index of the changed option
+    pub selected_id: usize,
+    /// all selected values (in selection order)
+    pub values: Vec<String>,
+}
@@ -6,7 +6,7 @@ use crate::{
     utils::{get_font_family, BoolToF32, ThemeColor},
 };
 
-use super::{GSelectItemClickedParam, GSelectItemEvent};
+use super::{types::SelectOption, GSelectItemClickedParam, GSelectItemEvent};
 
 live_design! {
     link gen_base;
@@ -163,6 +163,13 @@ pub struct GSelectItem {
     pub font_family: LiveDependency,
     #[live(true)]
     pub event_key: bool,
+    #[live]
+    pub disabled: bool,
+    #[live]
+    pub disabled_color: Option<Vec4>,
+    /// highlighted by keyboard navigation, drawn as hover
+    #[rust]
+    pub highlighted: bool,
 }
 
 impl LiveHook for GSelectItem {
@@ -219,15 +226,49 @@ impl GSelectItem {
     pub fn area(&self) -> Area {
         self.draw_item.area()
     }
-    pub fn draw_item(&mut self, cx: &mut Cx2d, text: &str, value: &str, theme: Themes) {
+    /// ## draw the item
+    /// `selected` and `disabled` come from the select option, so the item state always follows the data
+    pub fn draw_item(
+        &mut self,
+        cx: &mut Cx2d,
+        option: &SelectOption,
+        selected: bool,
+        highlighted: bool,
+        theme: Themes,
+    ) {
         self.theme = theme;
+        if self.selected != selected {
+            self.selected = selected;
+            if selected {
+                self.animator_cut(cx, id!(select.on));
+            } else {
+                self.animator_cut(cx, id!(select.off));
+            }
+        }
+        if self.highlighted != highlighted {
+            self.highlighted = highlighted;
+            if highlighted {
+                self.animator_cut(cx, id!(hover.on));
+            } else {
+                self.animator_cut(cx, id!(hover.off));
+            }
+        }
+        if self.disabled != option.disabled && option.disabled {
+            self.animator_cut(cx, id!(hover.off));
+        }
+        self.disabled = option.disabled;
         let _ = self.draw_item.begin(cx, self.walk, self.layout);
         let _ = get_font_family(&self.font_family, cx, &mut self.draw_text.text_style.font);
+        let color = self.draw_text.color;
+        if self.disabled {
+            self.draw_text.color = self.disabled_color.use_or("#98A2B3").unwrap();
+        }
         let _ = self
             .draw_text
-            .draw_walk(cx, Walk::fit(), Align::default(), text);
-        self.value = value.to_string();
-        self.text = text.to_string();
+            .draw_walk(cx, Walk::fit(), Align::default(), &option.text);
+        self.draw_text.color = color;
+        self.value = option.value.to_string();
+        self.text = option.text.to_string();
         let _ = self.draw_item.end(cx);
     }
     pub fn handle_event_with(
@@ -240,6 +281,9 @@ impl GSelectItem {
         if self.animator_handle_event(cx, event).must_redraw() {
             self.draw_item.area().redraw(cx);
         }
+        if self.disabled {
+            return;
+        }
         match event.hits_with_options(
             cx,
             self.area(),
@@ -7,13 +7,19 @@ mod types;
 use event::*;
 pub use item::*;
 use options::GSelectOptions;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::mpsc::{channel, Receiver, Sender},
+};
 
 use makepad_widgets::*;
-use types::SelectOption;
+pub use types::{SelectLoader, SelectLoaderSender, SelectOption, SelectRow};
 
+use super::{input::GInput, loading::GLoading, popup::PopupStackGlobal, tag::GTag};
 use crate::{
-    shader::{draw_text::DrawGText, draw_view::DrawGView},
+    event_option, ref_event_option, set_event, set_scope_path,
+    shader::{draw_text::DrawGText, draw_view::DrawGView, manual::Position},
     themes::Themes,
     utils::{get_font_family, set_cursor, BoolToF32, ThemeColor},
     widget_area,
@@ -82,6 +88,56 @@ live_design! {
     }
 }
 
+/// # GSelect
+/// A select component, the options are set from code.
+///
+/// ## Example
+/// ```rust
+/// let select = self.ui.gselect(id!(select));
+/// select.set_options(cx, vec![
+///     SelectOption::new("Apple", "apple").with_group("Fruit"),
+///     SelectOption::new("Banana", "banana").with_group("Fruit").with_disabled(true),
+///     SelectOption::new("Carrot", "carrot").with_group("Vegetable"),
+/// ]);
+/// select.set_selected_by_value(cx, "carrot");
+///
+/// if let Some(e) = select.changed(&actions) {
+///     log!("{} selected", e.value);
+/// }
+/// ```
+///
+/// ## Event
+/// - `Changed(GSelectOptionsChangedParam)`: an option is clicked, `values` are all selected values
+/// - `ValuesChanged(GSelectValuesChangedParam)`: the selection is changed by the keyboard, a tag close or `remove_option`
+///
+/// ## Multiple
+/// Set `multiple: true` to select more than one option, the selected options are drawn as closeable `GTag`s
+/// in the field and checked in the options list, the options list keeps open after an option is clicked.
+/// Use `max_selected` to limit the count of the selected options (`0` means no limit).
+///
+/// ## Filter
+/// Set `filterable: true` to draw a `GInput` at the top of the options list, options are filtered by the text.
+/// For remote search, set a loader, it is called (debounced by `filter_debounce`) with the filter text
+/// and a sender, a `GLoading` is drawn until the result is sent back.
+/// ```rust
+/// select.set_loader(|text, sender| {
+///     let text = text.to_string();
+///     std::thread::spawn(move || sender.send(search_remote(&text)));
+/// });
+/// ```
+///
+/// ## Keyboard
+/// - `Space` / `Enter` / `Alt + ArrowDown`: open the options list
+/// - `ArrowUp` / `ArrowDown` / `Home` / `End`: move the highlighted option (disabled options are skipped)
+/// - `Enter`: select the highlighted option, `Escape`: close
+/// - type the first letters of an option to jump to it (when not `filterable`)
+///
+/// The key focus returns to the select after the list is closed by keyboard or by selecting an option.
+///
+/// ## Large lists
+/// Only the rows in the view of the options popup are drawn, so every item has the same height
+/// (`item_height` of `select_options`). The popup grows with the content until `max_height`, then scrolls,
+/// the selected option is scrolled into view on open.
 #[derive(Live, Widget)]
 pub struct GSelect {
     #[live]
@@ -131,8 +187,12 @@ pub struct GSelect {
     pub opened: bool,
     #[live(6.0)]
     pub offset: f32,
+    /// preferred side of the options popup, it is flipped if there is no space in the window
+    #[live]
+    pub position: Position,
+    /// changed only by `set_options`, `push_option` and `remove_option`, so the cached rows are rebuilt
     #[rust]
-    pub options: Vec<SelectOption>,
+    options: Vec<SelectOption>,
     #[walk]
     pub walk: Walk,
     #[layout]
@@ -145,8 +205,87 @@ pub struct GSelect {
     animator: Animator,
     #[live(true)]
     pub event_key: bool,
+    #[rust]
+    pub scope_path: Option<HeapLiveIdPath>,
+    // multiple ------------------
+    #[live]
+    pub multiple: bool,
+    /// max count of the selected options in multiple mode, `0` means no limit
+    #[live(0)]
+    pub max_selected: usize,
+    #[live]
+    pub select_tag: Option<LivePtr>,
+    #[live(4.0)]
+    pub tag_spacing: f64,
+    /// selected values in multiple mode (in selection order)
+    #[rust]
+    pub selected_values: Vec<String>,
+    #[rust]
+    tags: ComponentMap<LiveId, GTag>,
+    // filter --------------------
+    #[live]
+    pub filterable: bool,
+    #[live]
+    pub filter_input: Option<LivePtr>,
+    /// delay (seconds) before the loader is called after the filter text changed
+    #[live(0.3)]
+    pub filter_debounce: f64,
+    #[live]
+    pub loading: Option<LivePtr>,
+    /// text drawn when no option matches the filter
+    #[live]
+    pub empty_text: String,
+    #[rust]
+    pub filter_text: String,
+    #[rust]
+    filter: Option<GInput>,
+    /// the filter is created in the first draw after open, it gets the key focus after that draw
+    #[rust]
+    focus_filter: bool,
+    #[rust]
+    loading_view: Option<GLoading>,
+    #[rust]
+    pub is_loading: bool,
+    #[rust]
+    loader: Option<SelectLoader>,
+    #[rust]
+    loader_channel: Option<(Sender<(u64, Vec<SelectOption>)>, Receiver<(u64, Vec<SelectOption>)>)>,
+    #[rust]
+    query_id: u64,
+    #[rust]
+    debounce_timer: Timer,
+    // keyboard ------------------
+    /// the index of the option highlighted by keyboard
+    #[rust]
+    pub highlighted: Option<usize>,
+    /// type-ahead buffer is reset after this time (seconds)
+    #[live(1.0)]
+    pub type_ahead_timeout: f64,
+    #[rust]
+    type_ahead: String,
+    #[rust]
+    type_ahead_time: Option<f64>,
+    // cache ---------------------
+    /// rows of the last filter, rebuilt when the options or the filter change
+    #[rust]
+    rows_cache: Option<Rc<Vec<SelectRow>>>,
+    /// visibility of each option in `rows_cache`
+    #[rust]
+    visible: Vec<bool>,
+    /// lowercased text of each option
+    #[rust]
+    lower_texts: Vec<String>,
+    // scroll --------------------
+    #[rust]
+    reset_scroll: bool,
+    /// scroll the highlighted option into view in next draw
+    #[rust]
+    follow_highlight: bool,
 }
 
+/// height of the loading row
+const LOADING_HEIGHT: f64 = 48.0;
+
 #[derive(Default, Clone)]
 pub struct SelectOptionsGlobal {
     pub map: Rc<RefCell<ComponentMap<LivePtr, GSelectOptions>>>,
@@ -157,11 +296,14 @@ impl Widget for GSelect {
         if !self.visible {
             return DrawStep::done();
         }
+        self.set_scope_path(&scope.path);
         let _ = self.draw_select.begin(cx, walk, self.layout);
 
-        if (self.options.len() >= self.selected) && self.options.len() != 0 {
+        if self.multiple {
+            self.draw_tags(cx, scope);
+        } else if let Some(option) = self.options.get(self.selected) {
             let _ = get_font_family(&self.font_family, cx, &mut self.draw_text.text_style.font);
-            let text = self.options[self.selected].text.to_string();
+            let text = option.text.to_string();
             self.draw_text
                 .draw_walk(cx, Walk::fit(), Align { x: 0.0, y: 0.5 }, &text);
         }
@@ -170,58 +312,214 @@ impl Widget for GSelect {
         cx.add_nav_stop(self.area(), NavRole::DropDown, Margin::default());
 
         if self.opened && self.select_options.is_some() {
+            let rows = self.rows();
             let global = cx.global::<SelectOptionsGlobal>().clone();
             let mut map = global.map.borrow_mut();
             let options_menu = map.get_mut(&self.select_options.unwrap()).unwrap();
-            // begin draw options
-            options_menu.begin(cx, self.theme);
-            // set item live ptr and draw
+            // set item live ptr
             options_menu.item = self.select_item.clone();
+            options_menu.multiple = self.multiple;
+
+            let filter_height = if self.filterable {
+                let walk = self
+                    .filter
+                    .get_or_insert_with(|| GInput::new_from_ptr(cx, self.filter_input))
+                    .walk(cx);
+                match walk.height {
+                    Size::Fixed(height) => height + walk.margin.top + walk.margin.bottom,
+                    _ => 0.0,
+                }
+            } else {
+                0.0
+            };
+            let (header_height, item_height) = (options_menu.header_height, options_menu.item_height);
+            let row_height = |row: &SelectRow| match row {
+                SelectRow::Header(_) => header_height,
+                SelectRow::Option(_) => item_height,
+            };
+            let mut content_height = filter_height + rows.iter().map(row_height).sum::<f64>();
+            if self.is_loading {
+                content_height += LOADING_HEIGHT;
+            } else if rows.is_empty() && !self.empty_text.is_empty() {
+                content_height += header_height;
+            }
+            // scroll before begin, so the window is right in this frame
+            let view_height = options_menu.view_height(content_height);
+            if let Some(view_height) = view_height {
+                if self.reset_scroll {
+                    options_menu.set_scroll_y(cx, 0.0);
+                }
+                let highlighted = self.highlighted.filter(|_| self.follow_highlight);
+                if let Some(y) = highlighted.and_then(|highlighted| {
+                    let mut y = filter_height;
+                    for row in rows.iter() {
+                        if *row == SelectRow::Option(highlighted) {
+                            return Some(y);
+                        }
+                        y += row_height(row);
+                    }
+                    None
+                }) {
+                    let scroll = options_menu.scroll_pos().y;
+                    if y < scroll {
+                        options_menu.set_scroll_y(cx, y);
+                    } else if y + item_height > scroll + view_height {
+                        options_menu.set_scroll_y(cx, y + item_height - view_height);
+                    }
+                }
+            }
+            self.reset_scroll = false;
+            self.follow_highlight = false;
+
+            // begin draw options
+            options_menu.raise = PopupStackGlobal::take_raise(cx, self.widget_uid());
+            options_menu.begin(cx, self.theme, content_height);
+
+            if let Some(filter) = self.filter.as_mut().filter(|_| self.filterable) {
+                let walk = filter.walk(cx);
+                let _ = filter.draw_walk(cx, scope, walk);
+                if self.focus_filter {
+                    self.focus_filter = false;
+                    filter.set_key_focus(cx);
+                }
+            }
+
+            // only draw the rows in the view, the others are replaced by spacers
+            let top = options_menu.scroll_pos().y - filter_height;
+            let bottom = top + view_height.unwrap_or(f64::INFINITY);
+            let mut y = 0.0;
+            let mut space_before = 0.0;
+            let mut space_after = 0.0;
+            options_menu.clear_slots();
+            for row in rows.iter() {
+                let height = row_height(row);
+                if y + height <= top {
+                    space_before += height;
+                } else if y >= bottom {
+                    space_after += height;
+                } else {
+                    if space_before > 0.0 {
+                        cx.walk_turtle(Walk::size(Size::Fill, Size::Fixed(space_before)));
+                        space_before = 0.0;
+                    }
+                    match *row {
+                        SelectRow::Header(index) => {
+                            let group = self.options[index].group.as_deref().unwrap_or_default();
+                            options_menu.draw_header(cx, group);
+                        }
+                        SelectRow::Option(index) => {
+                            options_menu.draw_option(
+                                cx,
+                                index,
+                                &self.options[index],
+                                self.is_selected(index),
+                                self.highlighted == Some(index),
+                            );
+                        }
+                    }
+                }
+                y += height;
+            }
+            if space_before + space_after > 0.0 {
+                cx.walk_turtle(Walk::size(Size::Fill, Size::Fixed(space_before + space_after)));
+            }
 
-            for (index, option) in self.options.iter().enumerate() {
-                options_menu.draw_option(cx, LiveId(index as u64), &option.text, &option.value);
+            if self.is_loading {
+                let loading = self
+                    .loading_view
+                    .get_or_insert_with(|| GLoading::new_from_ptr(cx, self.loading));
+                cx.begin_turtle(
+                    Walk {
+                        width: Size::Fill,
+                        height: Size::Fixed(LOADING_HEIGHT),
+                        ..Default::default()
+                    },
+                    Layout {
+                        align: Align { x: 0.5, y: 0.5 },
+                        ..Default::default()
+                    },
+                );
+                let walk = loading.walk(cx);
+                let _ = loading.draw_walk(cx, scope, walk);
+                cx.end_turtle();
+            } else if rows.is_empty() && !self.empty_text.is_empty() {
+                options_menu.draw_header(cx, &self.empty_text);
             }
 
             let _ = options_menu.end_container(cx);
             let area = self.area().rect(cx);
             let container_size = options_menu.area().rect(cx).size;
-
-            let shift = DVec2 {
-                x: area.size.x / 2.0 - container_size.x / 2.0,
-                y: area.size.y + self.offset as f64,
+            let viewport = Rect {
+                pos: DVec2::default(),
+                size: cx.current_pass_size(),
             };
+            // flip and slide the options to stay in the window
+            let (_, shift) = self.position.place(area, container_size, self.offset as f64, viewport);
             options_menu.end(cx, scope, self.area(), shift);
+            PopupStackGlobal::set_popup_area(cx, self.widget_uid(), options_menu.area());
         }
         DrawStep::done()
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.animator_handle_event(cx, event);
-        let uid = self.widget_uid();
+        if let Event::Signal = event {
+            self.poll_loader(cx);
+        }
+        if self.debounce_timer.is_event(event).is_some() {
+            self.load(cx);
+        }
+        // a popup opened above the options list takes the keys and the pointer events over it
+        if !PopupStackGlobal::accepts(cx, self.widget_uid(), event) {
+            return;
+        }
+        if self.multiple && self.handle_tags(cx, event, scope) {
+            return;
+        }
+        if self.opened {
+            if let Some(loading) = self.loading_view.as_mut().filter(|_| self.is_loading) {
+                loading.handle_event(cx, event, scope);
+            }
+            if let Some(filter) = self.filter.as_mut() {
+                let actions = cx.capture_actions(|cx| filter.handle_event(cx, event, scope));
+                if let Some(param) = filter.changed(&actions) {
+                    self.set_filter_text(cx, param.text);
+                }
+            }
+        }
+        // the key focus may be on the filter input, so the keys are handled before hit test
+        if self.opened && self.handle_key_opened(cx, event, scope) {
+            return;
+        }
         if self.opened && self.select_options.is_some() {
             let global = cx.global::<SelectOptionsGlobal>().clone();
             let mut map = global.map.borrow_mut();
             let menu = map.get_mut(&self.select_options.unwrap()).unwrap();
-            let mut close = false;
+            let mut changed = None;
+            let scroll = menu.scroll_pos();
 
-            menu.handle_event_with(cx, event, self.area(), &mut |cx, action| match action {
+            menu.handle_event_with(cx, event, self.area(), &mut |_, action| match action {
                 GSelectOptionsEvent::Changed(e) => {
-                    self.selected = e.selected_id;
-                    cx.widget_action(uid, &scope.path, GSelectEvent::Changed(e));
-                    self.draw_select.redraw(cx);
-                    close = true;
+                    changed.replace(e);
                 }
                 _ => (),
             });
+            let mut close = false;
+            if let Event::MouseDown(e) = event {
+                close = !menu.menu_contains_pos(cx, e.abs);
+            }
+            let scrolled = menu.scroll_pos() != scroll;
+            drop(map);
+            // the rows in the view are changed, draw the new window
+            if scrolled {
+                self.redraw_all(cx);
+            }
+            if let Some(e) = changed {
+                self.toggle_option(cx, &scope.path, e.selected_id, Some(e.e));
+            }
             if close {
                 self.close(cx);
             }
-            if let Event::MouseDown(e) = event {
-                if !menu.menu_contains_pos(cx, e.abs) {
-                    self.close(cx);
-                    // self.animator_play(cx, id!(hover.off));
-                }
-            }
         }
 
         match event.hits_with_sweep_area(cx, self.area(), self.area()) {
@@ -229,6 +527,20 @@ impl Widget for GSelect {
                 set_cursor(cx, self.cursor.as_ref());
                 self.animator_play(cx, id!(hover.on));
             }
+            Hit::FingerDown(_) => {
+                cx.set_key_focus(self.area());
+            }
+            Hit::KeyDown(e) => match e.key_code {
+                KeyCode::Space | KeyCode::ReturnKey => self.open(cx),
+                KeyCode::ArrowDown if e.modifiers.alt => self.open(cx),
+                _ => (),
+            },
+            Hit::TextInput(e) => {
+                if !self.filterable && !e.input.trim().is_empty() {
+                    self.open(cx);
+                    self.type_ahead(cx, &e.input);
+                }
+            }
             Hit::FingerUp(f) => {
                 if f.is_over && f.device.has_hovers() {
                     set_cursor(cx, self.cursor.as_ref());
@@ -299,8 +611,503 @@ impl GSelect {
     widget_area! {
         area, draw_select
     }
+    set_scope_path!();
+    event_option! {
+        changed: GSelectEvent::Changed => GSelectOptionsChangedParam,
+        values_changed: GSelectEvent::ValuesChanged => GSelectValuesChangedParam
+    }
+    pub fn is_selected(&self, index: usize) -> bool {
+        if self.multiple {
+            self.options
+                .get(index)
+                .map_or(false, |option| self.selected_values.contains(&option.value))
+        } else {
+            index == self.selected
+        }
+    }
+    /// ## select (or unselect in multiple mode) the option by index and send `Changed` (or `ValuesChanged` without `e`)
+    /// - single mode: select the option and close the options list
+    /// - multiple mode: toggle the option, if `max_selected` is reached, nothing happens
+    fn toggle_option(
+        &mut self,
+        cx: &mut Cx,
+        path: &HeapLiveIdPath,
+        index: usize,
+        e: Option<FingerUpEvent>,
+    ) {
+        let Some(option) = self.options.get(index).cloned() else {
+            return;
+        };
+        let selected = if self.multiple {
+            if let Some(pos) = self.selected_values.iter().position(|v| *v == option.value) {
+                self.selected_values.remove(pos);
+                false
+            } else if self.max_selected == 0 || self.selected_values.len() < self.max_selected {
+                self.selected_values.push(option.value.to_string());
+                true
+            } else {
+                // the item state is synced from the data in next draw
+                self.redraw_all(cx);
+                return;
+            }
+        } else {
+            self.selected = index;
+            true
+        };
+        self.retain_tags();
+        self.redraw_all(cx);
+        if !self.multiple {
+            self.close(cx);
+            cx.set_key_focus(self.area());
+        }
+        self.emit_changed(cx, path, selected, option, index, e);
+    }
+    /// send `Changed` if the change is made by a click, otherwise `ValuesChanged`
+    fn emit_changed(
+        &self,
+        cx: &mut Cx,
+        path: &HeapLiveIdPath,
+        selected: bool,
+        option: SelectOption,
+        selected_id: usize,
+        e: Option<FingerUpEvent>,
+    ) {
+        let values = self.get_selected_values();
+        let event = match e {
+            Some(e) => GSelectEvent::Changed(GSelectOptionsChangedParam {
+                selected,
+                text: option.text,
+                value: option.value,
+                selected_id,
+                values,
+                e,
+            }),
+            None => GSelectEvent::ValuesChanged(GSelectValuesChangedParam {
+                selected,
+                text: option.text,
+                value: option.value,
+                selected_id,
+                values,
+            }),
+        };
+        cx.widget_action(self.widget_uid(), path, event);
+    }
+    /// all selected values, in single mode it only contains the selected option's value
+    pub fn get_selected_values(&self) -> Vec<String> {
+        if self.multiple {
+            self.selected_values.clone()
+        } else {
+            self.selected_value().into_iter().collect()
+        }
+    }
+    /// ## set the selected values in multiple mode
+    /// values which are not in the options are ignored, `max_selected` is respected
+    pub fn set_selected_values(&mut self, cx: &mut Cx, values: Vec<String>) {
+        self.selected_values = values
+            .into_iter()
+            .filter(|value| self.options.iter().any(|option| option.value == *value))
+            .collect();
+        if self.max_selected > 0 {
+            self.selected_values.truncate(self.max_selected);
+        }
+        self.retain_tags();
+        self.redraw_all(cx);
+    }
+    fn draw_tags(&mut self, cx: &mut Cx2d, scope: &mut Scope) {
+        cx.begin_turtle(
+            Walk {
+                width: Size::Fill,
+                height: Size::Fit,
+                ..Default::default()
+            },
+            Layout {
+                flow: Flow::RightWrap,
+                spacing: self.tag_spacing,
+                align: Align { x: 0.0, y: 0.5 },
+                ..Default::default()
+            },
+        );
+        for (index, value) in self.selected_values.iter().enumerate() {
+            let Some(option) = self.options.iter().find(|option| option.value == *value) else {
+                continue;
+            };
+            let tag = self.tags.get_or_insert(cx, LiveId(index as u64), |cx| {
+                GTag::new_from_ptr(cx, self.select_tag)
+            });
+            tag.text.as_mut_empty().push_str(&option.text);
+            let walk = tag.walk(cx);
+            let _ = tag.draw_walk(cx, scope, walk);
+        }
+        cx.end_turtle();
+    }
+    /// handle the events of the tags, back `true` if a tag is closed
+    fn handle_tags(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) -> bool {
+        let mut closed = None;
+        for (id, tag) in self.tags.iter_mut() {
+            let actions = cx.capture_actions(|cx| tag.handle_event(cx, event, scope));
+            if tag.closed(&actions).is_some() {
+                closed = Some(id.0 as usize);
+            }
+        }
+        let Some(value) = closed.and_then(|index| self.selected_values.get(index).cloned()) else {
+            return false;
+        };
+        if let Some(index) = self.options.iter().position(|option| option.value == value) {
+            self.toggle_option(cx, &scope.path, index, None);
+        }
+        true
+    }
+    /// handle the keys when the options list is opened, back `true` if the key is used
+    fn handle_key_opened(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) -> bool {
+        match event {
+            // keys of other widgets are not taken while the list is open
+            Event::KeyDown(e) if self.has_key_focus(cx) => {
+                match e.key_code {
+                    KeyCode::Escape => {
+                        self.close(cx);
+                        cx.set_key_focus(self.area());
+                    }
+                    KeyCode::ArrowDown => self.move_highlight(cx, 1),
+                    KeyCode::ArrowUp => self.move_highlight(cx, -1),
+                    KeyCode::Home if !self.filterable => {
+                        self.highlighted = None;
+                        self.move_highlight(cx, 1);
+                    }
+                    KeyCode::End if !self.filterable => {
+                        self.highlighted = None;
+                        self.move_highlight(cx, -1);
+                    }
+                    KeyCode::ReturnKey => {
+                        if let Some(index) = self.highlighted {
+                            self.toggle_option(cx, &scope.path, index, None);
+                        }
+                    }
+                    _ => return false,
+                }
+                true
+            }
+            Event::TextInput(e) if !self.filterable && cx.has_key_focus(self.area()) => {
+                if !e.input.trim().is_empty() {
+                    self.type_ahead(cx, &e.input);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+    /// the select or its filter input has the key focus
+    fn has_key_focus(&self, cx: &Cx) -> bool {
+        cx.has_key_focus(self.area())
+            || self
+                .filter
+                .as_ref()
+                .map_or(false, |filter| cx.has_key_focus(filter.area()))
+    }
+    /// options which can be highlighted (visible and not disabled)
+    /// `rows` should be called before, so the visibility is up to date
+    fn is_option_enabled(&self, index: usize) -> bool {
+        !self.is_loading
+            && self.visible.get(index).copied().unwrap_or(false)
+            && self.options.get(index).map_or(false, |option| !option.disabled)
+    }
+    /// move the highlighted option by `step` (`1` or `-1`), wrap around at the ends
+    fn move_highlight(&mut self, cx: &mut Cx, step: isize) {
+        let _ = self.rows();
+        let len = self.options.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let mut index = match self.highlighted {
+            Some(index) => index as isize,
+            None if step > 0 => -1,
+            None => len,
+        };
+        for _ in 0..len {
+            index = (index + step).rem_euclid(len);
+            if self.is_option_enabled(index as usize) {
+                self.highlighted = Some(index as usize);
+                self.follow_highlight = true;
+                self.redraw_all(cx);
+                return;
+            }
+        }
+    }
+    /// ## jump to the option which starts with the typed letters
+    /// the letters are collected until `type_ahead_timeout`, typing the same letter cycles through the options
+    fn type_ahead(&mut self, cx: &mut Cx, input: &str) {
+        let now = cx.seconds_since_app_start();
+        if self
+            .type_ahead_time
+            .map_or(true, |time| now - time > self.type_ahead_timeout)
+        {
+            self.type_ahead.clear();
+        }
+        self.type_ahead_time = Some(now);
+        self.type_ahead.push_str(&input.to_lowercase());
+        let _ = self.rows();
+
+        let len = self.options.len();
+        let mut chars = self.type_ahead.chars();
+        let first = chars.next();
+        let repeated = chars.all(|c| Some(c) == first);
+        // a new search starts at the highlighted option, a repeated letter moves to the next one
+        let start = match self.highlighted {
+            Some(index) if repeated => index + 1,
+            Some(index) => index,
+            None => 0,
+        };
+        let prefix = if repeated {
+            first.map(String::from).unwrap_or_default()
+        } else {
+            self.type_ahead.to_string()
+        };
+        let found = (0..len).map(|i| (start + i) % len).find(|index| {
+            self.is_option_enabled(*index)
+                && self.lower_texts[*index].starts_with(&prefix)
+        });
+        if let Some(index) = found {
+            self.highlighted = Some(index);
+            self.follow_highlight = true;
+            self.redraw_all(cx);
+        }
+    }
+    /// the rows to draw: visible options and their group headers
+    fn rows(&mut self) -> Rc<Vec<SelectRow>> {
+        if self.lower_texts.len() != self.options.len() {
+            self.lower_texts = self
+                .options
+                .iter()
+                .map(|option| option.text.to_lowercase())
+                .collect();
+            self.rows_cache = None;
+        }
+        if self.is_loading {
+            return Rc::new(Vec::new());
+        }
+        if let Some(rows) = self.rows_cache.as_ref() {
+            return rows.clone();
+        }
+        // remote result is not filtered again
+        let filter = self.filter_text.to_lowercase();
+        let show_all = self.loader.is_some() || filter.is_empty();
+        self.visible = self
+            .lower_texts
+            .iter()
+            .map(|text| show_all || text.contains(&filter))
+            .collect();
+        let mut rows = Vec::new();
+        let mut group = None;
+        for (index, option) in self.options.iter().enumerate() {
+            if !self.visible[index] {
+                continue;
+            }
+            if option.group.is_some() && option.group.as_ref() != group {
+                group = option.group.as_ref();
+                rows.push(SelectRow::Header(index));
+            }
+            rows.push(SelectRow::Option(index));
+        }
+        let rows = Rc::new(rows);
+        self.rows_cache.replace(rows.clone());
+        rows
+    }
+    /// the options changed, the lowercased texts and the rows are rebuilt in next `rows`
+    fn options_changed(&mut self) {
+        self.lower_texts.clear();
+        self.rows_cache = None;
+    }
+    /// set the filter text, if a loader is set, the loader will be called after `filter_debounce`
+    pub fn set_filter_text(&mut self, cx: &mut Cx, text: String) {
+        self.filter_text = text;
+        self.rows_cache = None;
+        self.reset_scroll = true;
+        if self.loader.is_some() {
+            cx.stop_timer(self.debounce_timer);
+            if self.filter_debounce > 0.0 {
+                self.debounce_timer = cx.start_timeout(self.filter_debounce);
+            } else {
+                self.load(cx);
+            }
+        }
+        self.redraw_all(cx);
+    }
+    pub fn set_loader<F>(&mut self, loader: F)
+    where
+        F: Fn(&str, SelectLoaderSender) + 'static,
+    {
+        self.loader.replace(Rc::new(loader));
+        self.rows_cache = None;
+    }
+    pub fn clear_loader(&mut self, cx: &mut Cx) {
+        self.loader = None;
+        self.rows_cache = None;
+        self.is_loading = false;
+        self.redraw_all(cx);
+    }
+    /// call the loader with the current filter text
+    pub fn load(&mut self, cx: &mut Cx) {
+        let Some(loader) = self.loader.clone() else {
+            return;
+        };
+        self.query_id += 1;
+        let (sender, _) = self.loader_channel.get_or_insert_with(channel);
+        let sender = SelectLoaderSender {
+            query_id: self.query_id,
+            sender: sender.clone(),
+        };
+        self.is_loading = true;
+        self.redraw_all(cx);
+        loader(&self.filter_text, sender);
+    }
+    fn poll_loader(&mut self, cx: &mut Cx) {
+        let Some((_, receiver)) = self.loader_channel.as_ref() else {
+            return;
+        };
+        let mut latest = None;
+        while let Ok((query_id, options)) = receiver.try_recv() {
+            if query_id == self.query_id {
+                latest.replace(options);
+            }
+        }
+        if let Some(options) = latest {
+            self.is_loading = false;
+            self.set_remote_options(cx, options);
+        }
+    }
+    /// set the options from the loader, the selected options which are not in the result
+    /// are kept at the end, so the selection is not lost by a search
+    fn set_remote_options(&mut self, cx: &mut Cx, mut options: Vec<SelectOption>) {
+        let kept: Vec<SelectOption> = if self.multiple {
+            self.options
+                .iter()
+                .filter(|option| self.selected_values.contains(&option.value))
+                .cloned()
+                .collect()
+        } else {
+            self.options.get(self.selected).cloned().into_iter().collect()
+        };
+        for option in kept {
+            if !options.iter().any(|o| o.value == option.value) {
+                options.push(option);
+            }
+        }
+        self.set_options(cx, options);
+    }
+    fn retain_tags(&mut self) {
+        let len = self.selected_values.len();
+        self.tags.retain(|id, _| (id.0 as usize) < len);
+    }
+    /// ## set the options
+    /// the selected option is kept by value, if it does not exist anymore the first option is selected
+    pub fn set_options(&mut self, cx: &mut Cx, options: Vec<SelectOption>) {
+        let selected = self.options.get(self.selected).map(|option| option.value.to_string());
+        self.options = options;
+        self.options_changed();
+        self.selected = selected
+            .and_then(|value| self.options.iter().position(|option| option.value == value))
+            .unwrap_or(0);
+        let options = &self.options;
+        self.selected_values
+            .retain(|value| options.iter().any(|option| option.value == *value));
+        self.retain_tags();
+        self.redraw_all(cx);
+    }
+    pub fn options(&self) -> &[SelectOption] {
+        &self.options
+    }
+    pub fn push_option(&mut self, cx: &mut Cx, option: SelectOption) {
+        self.options.push(option);
+        self.options_changed();
+        self.redraw_all(cx);
+    }
+    /// ## remove the first option which has the value
+    /// the selected index is kept on the same option if it still exists.
+    /// If the removed option is selected, `ValuesChanged` is sent (once the select is drawn):
+    /// - single mode: the next option (or the last one) is selected, the param is that option with `selected: true`,
+    ///   if no option is left, the param is the removed option with `selected: false`
+    /// - multiple mode: the removed option is unselected, the param is it with `selected: false`
+    pub fn remove_option(&mut self, cx: &mut Cx, value: &str) -> Option<SelectOption> {
+        let index = self.options.iter().position(|option| option.value == value)?;
+        let was_selected = self.is_selected(index);
+        let removed = self.options.remove(index);
+        self.options_changed();
+        if index < self.selected || self.selected >= self.options.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if !self.options.iter().any(|option| option.value == value) {
+            self.selected_values.retain(|v| v != value);
+            self.retain_tags();
+        }
+        self.redraw_all(cx);
+        if was_selected && self.event_key {
+            if let Some(path) = self.scope_path.clone() {
+                let (selected, option, selected_id) = match self.options.get(self.selected) {
+                    Some(option) if !self.multiple => (true, option.clone(), self.selected),
+                    _ => (false, removed.clone(), index),
+                };
+                self.emit_changed(cx, &path, selected, option, selected_id, None);
+            }
+        }
+        Some(removed)
+    }
+    /// ## set the selected option by value, back `false` if no option has the value
+    /// in multiple mode, the selected values are replaced by this value
+    pub fn set_selected_by_value(&mut self, cx: &mut Cx, value: &str) -> bool {
+        if let Some(index) = self.options.iter().position(|option| option.value == value) {
+            self.selected = index;
+            if self.multiple {
+                self.selected_values = vec![value.to_string()];
+                self.retain_tags();
+            }
+            self.redraw_all(cx);
+            true
+        } else {
+            false
+        }
+    }
+    /// the selected option, in multiple mode it is the first selected option
+    pub fn selected_option(&self) -> Option<&SelectOption> {
+        if self.multiple {
+            self.selected_values.first().and_then(|value| {
+                self.options.iter().find(|option| option.value == *value)
+            })
+        } else {
+            self.options.get(self.selected)
+        }
+    }
+    pub fn selected_value(&self) -> Option<String> {
+        self.selected_option().map(|option| option.value.to_string())
+    }
+    fn redraw_all(&mut self, cx: &mut Cx) {
+        self.draw_select.redraw(cx);
+        if self.opened {
+            if let Some(ptr) = self.select_options.as_ref() {
+                let global = cx.global::<SelectOptionsGlobal>().clone();
+                let mut map = global.map.borrow_mut();
+                if let Some(menu) = map.get_mut(ptr) {
+                    menu.redraw(cx);
+                }
+            }
+        }
+    }
     pub fn open(&mut self, cx: &mut Cx) {
         self.opened = true;
+        self.type_ahead.clear();
+        let selected = if self.multiple {
+            self.selected_values
+                .first()
+                .and_then(|value| self.options.iter().position(|option| option.value == *value))
+        } else {
+            Some(self.selected)
+        };
+        let _ = self.rows();
+        self.highlighted = selected.filter(|index| self.is_option_enabled(*index));
+        self.reset_scroll = true;
+        self.follow_highlight = true;
+        match self.filter.as_ref() {
+            Some(filter) => filter.set_key_focus(cx),
+            None => self.focus_filter = self.filterable,
+        }
         self.draw_select.apply_over(cx, live! {focus: 1.0});
         self.draw_select.redraw(cx);
         // let global = cx.global::<PopupMenuGlobal>().clone();
@@ -308,13 +1115,101 @@ impl GSelect {
         // let lb = map.get_mut(&self.popup_menu.unwrap()).unwrap();
         // let node_id = LiveId(self.selected_item as u64).into();
         // lb.init_select_item(node_id);
-        cx.sweep_lock(self.draw_select.area());
+        PopupStackGlobal::open(cx, self.widget_uid(), self.draw_select.area());
     }
 
     pub fn close(&mut self, cx: &mut Cx) {
         self.opened = false;
+        if let Some(filter) = self.filter.as_mut() {
+            Widget::set_text(filter, cx, "");
+        }
+        // the input is cleared, so is the query, the remote rows of the old query are reloaded for the empty one
+        if !self.filter_text.is_empty() {
+            self.filter_text.clear();
+            self.rows_cache = None;
+            cx.stop_timer(self.debounce_timer);
+            self.load(cx);
+        }
         self.draw_select.apply_over(cx, live! {focus: 0.0});
         self.draw_select.redraw(cx);
-        cx.sweep_unlock(self.draw_select.area());
+        PopupStackGlobal::close(cx, self.widget_uid());
+    }
+}
+
+impl GSelectRef {
+    ref_event_option! {
+        changed => GSelectOptionsChangedParam,
+        values_changed => GSelectValuesChangedParam
+    }
+    pub fn get_selected_values(&self) -> Vec<String> {
+        self.borrow()
+            .map_or(Vec::new(), |c_ref| c_ref.get_selected_values())
+    }
+    pub fn set_loader<F>(&self, loader: F)
+    where
+        F: Fn(&str, SelectLoaderSender) + 'static,
+    {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_loader(loader);
+        }
+    }
+    pub fn clear_loader(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.clear_loader(cx);
+        }
+    }
+    pub fn load(&self, cx: &mut Cx) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.load(cx);
+        }
+    }
+    pub fn set_filter_text(&self, cx: &mut Cx, text: String) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_filter_text(cx, text);
+        }
+    }
+    pub fn is_loading(&self) -> bool {
+        self.borrow().map_or(false, |c_ref| c_ref.is_loading)
+    }
+    pub fn set_selected_values(&self, cx: &mut Cx, values: Vec<String>) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_selected_values(cx, values);
+        }
+    }
+    pub fn set_options(&self, cx: &mut Cx, options: Vec<SelectOption>) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.set_options(cx, options);
+        }
+    }
+    pub fn get_options(&self) -> Vec<SelectOption> {
+        self.borrow()
+            .map_or(Vec::new(), |c_ref| c_ref.options.clone())
+    }
+    pub fn push_option(&self, cx: &mut Cx, option: SelectOption) {
+        if let Some(mut c_ref) = self.borrow_mut() {
+            c_ref.push_option(cx, option);
+        }
+    }
+    pub fn remove_option(&self, cx: &mut Cx, value: &str) -> Option<SelectOption> {
+        self.borrow_mut()
+            .and_then(|mut c_ref| c_ref.remove_option(cx, value))
+    }
+    pub fn set_selected_by_value(&self, cx: &mut Cx, value: &str) -> bool {
+        self.borrow_mut()
+            .map_or(false, |mut c_ref| c_ref.set_selected_by_value(cx, value))
+    }
+    pub fn selected_value(&self) -> Option<String> {
+        self.borrow().and_then(|c_ref| c_ref.selected_value())
+    }
+    pub fn selected_option(&self) -> Option<SelectOption> {
+        self.borrow()
+            .and_then(|c_ref| c_ref.selected_option().cloned())
+    }
+}
+
+impl GSelectSet {
+    set_event! {
+        changed => GSelectOptionsChangedParam,
+        values_changed => GSelectValuesChangedParam
     }
 }
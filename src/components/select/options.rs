@@ -1,17 +1,29 @@
 use makepad_widgets::*;
 
 use crate::{
-    shader::draw_view::DrawGView,
+    shader::{draw_text::DrawGText, draw_view::DrawGView},
     themes::Themes,
-    utils::{BoolToF32, ThemeColor},
+    utils::{get_font_family, BoolToF32, ThemeColor},
 };
 
-use super::{item::GSelectItem, GSelectItemEvent, GSelectOptionsChangedParam, GSelectOptionsEvent};
+use super::{
+    item::GSelectItem, types::SelectOption, GSelectItemEvent, GSelectOptionsChangedParam,
+    GSelectOptionsEvent,
+};
 
 live_design! {
     link gen_base;
+    use link::gen_theme::*;
 
-    pub GSelectOptionsBase = {{GSelectOptions}}{ }
+    pub GSelectOptionsBase = {{GSelectOptions}}{
+        font_family: (FONT_FAMILY),
+        header_padding: {left: 8.0, right: 8.0},
+        draw_header: {
+            fn get_color(self) -> vec4 {
+                return self.color;
+            }
+        }
+    }
 }
 
 #[derive(Live, LiveRegister)]
@@ -48,10 +60,18 @@ pub struct GSelectOptions {
     pub layout: Layout,
     #[live(true)]
     pub visible: bool,
+    /// items are keyed by the slot in the drawn window, they are reused by other options while scrolling,
+    /// so the count is bounded by the rows of the window, not by the options
     #[rust]
     pub children: ComponentMap<LiveId, GSelectItem>,
+    /// the option index of each slot drawn in this frame
+    #[rust]
+    pub slots: Vec<usize>,
     #[live]
     pub draw_list: DrawList2d,
+    /// draw the options as the last overlay in next `begin`, so they are above all other popups
+    #[rust]
+    pub raise: bool,
     #[live]
     pub item: Option<LivePtr>,
     // scroll ---------------------
@@ -59,6 +79,28 @@ pub struct GSelectOptions {
     pub scroll_bars: Option<LivePtr>,
     #[rust]
     pub scroll_bars_obj: Option<Box<ScrollBars>>,
+    // group header ---------------
+    #[live]
+    pub draw_header: DrawGText,
+    #[live]
+    pub header_color: Option<Vec4>,
+    #[live(8.0)]
+    pub header_font_size: f64,
+    #[live(28.0)]
+    pub header_height: f64,
+    #[live]
+    pub header_padding: Padding,
+    #[live]
+    pub font_family: LiveDependency,
+    /// multiple mode, the clicked item does not unselect others
+    #[live]
+    pub multiple: bool,
+    /// the height of each item, only the items in the view are drawn
+    #[live(36.0)]
+    pub item_height: f64,
+    /// the popup fits the content until this height, then it scrolls (`0.0` uses the walk height)
+    #[live(0.0)]
+    pub max_height: f64,
 }
 
 impl LiveHook for GSelectOptions {
@@ -95,6 +137,16 @@ impl LiveHook for GSelectOptions {
                 blur_radius: (self.blur_radius)
             },
         );
+        let header_color = self.header_color.use_or("#667085").unwrap();
+        self.draw_header.apply_over(
+            cx,
+            live! {
+                color: (header_color),
+                text_style: {
+                    font_size: (self.header_font_size),
+                }
+            },
+        );
         self.draw_options.redraw(cx);
     }
 }
@@ -108,10 +160,28 @@ impl GSelectOptions {
         self.draw_options.area().clipped_rect(cx).contains(pos)
     }
     /// ## Begin to draw popup
-    /// this method is used to begin drawing the popup
-    pub fn begin(&mut self, cx: &mut Cx2d, theme: Themes) {
-        self.draw_list.begin_overlay_reuse(cx);
+    /// this method is used to begin drawing the popup,
+    /// `content_height` is the height of all rows, it is used by `max_height` and to clamp the scroll
+    pub fn begin(&mut self, cx: &mut Cx2d, theme: Themes, content_height: f64) {
+        if self.raise {
+            self.raise = false;
+            self.draw_list.begin_overlay_last(cx);
+        } else {
+            self.draw_list.begin_overlay_reuse(cx);
+        }
         cx.begin_pass_sized_turtle(Layout::flow_down());
+        let mut walk = self.walk;
+        let padding = self.layout.padding.top + self.layout.padding.bottom;
+        if self.max_height > 0.0 {
+            walk.height = Size::Fixed((content_height + padding).min(self.max_height));
+        }
+        if let (Some(scroll_bars), Size::Fixed(height)) = (&mut self.scroll_bars_obj, walk.height) {
+            let max_scroll = (content_height + padding - height).max(0.0);
+            let scroll = scroll_bars.get_scroll_pos();
+            if scroll.y > max_scroll {
+                scroll_bars.set_scroll_pos_no_clip(cx, dvec2(scroll.x, max_scroll));
+            }
+        }
         let scroll = if let Some(scroll_bars) = &mut self.scroll_bars_obj {
             scroll_bars.begin_nav_area(cx);
             scroll_bars.get_scroll_pos()
@@ -119,9 +189,31 @@ impl GSelectOptions {
             self.layout.scroll
         };
         self.draw_options
-            .begin(cx, self.walk, self.layout.with_scroll(scroll));
+            .begin(cx, walk, self.layout.with_scroll(scroll));
         self.theme = theme;
     }
+    pub fn scroll_pos(&self) -> DVec2 {
+        self.scroll_bars_obj
+            .as_ref()
+            .map_or(self.layout.scroll, |scroll_bars| scroll_bars.get_scroll_pos())
+    }
+    pub fn set_scroll_y(&mut self, cx: &mut Cx, y: f64) {
+        if let Some(scroll_bars) = self.scroll_bars_obj.as_mut() {
+            let x = scroll_bars.get_scroll_pos().x;
+            scroll_bars.set_scroll_pos_no_clip(cx, dvec2(x, y.max(0.0)));
+        }
+    }
+    /// the height of the view (without padding), `None` if the height is not fixed
+    pub fn view_height(&self, content_height: f64) -> Option<f64> {
+        let padding = self.layout.padding.top + self.layout.padding.bottom;
+        if self.max_height > 0.0 {
+            Some((content_height + padding).min(self.max_height) - padding)
+        } else if let Size::Fixed(height) = self.walk.height {
+            Some(height - padding)
+        } else {
+            None
+        }
+    }
     pub fn end_container(&mut self, cx: &mut Cx2d) {
         if let Some(scroll_bars) = &mut self.scroll_bars_obj {
             scroll_bars.draw_scroll_bars(cx);
@@ -145,11 +237,45 @@ impl GSelectOptions {
         self.draw_list.redraw(cx);
         // self.draw_options.redraw(cx);
     }
-    pub fn draw_option(&mut self, cx: &mut Cx2d, item_id: LiveId, text: &str, value: &str) {
+    /// draw the option in the next free slot, `index` is the index of the option
+    pub fn draw_option(
+        &mut self,
+        cx: &mut Cx2d,
+        index: usize,
+        option: &SelectOption,
+        selected: bool,
+        highlighted: bool,
+    ) {
+        let item_id = LiveId(self.slots.len() as u64);
+        self.slots.push(index);
         let target = self
             .children
             .get_or_insert(cx, item_id, |cx| GSelectItem::new_from_ptr(cx, self.item));
-        target.draw_item(cx, text, value, self.theme);
+        target.walk.height = Size::Fixed(self.item_height);
+        target.draw_item(cx, option, selected, highlighted, self.theme);
+    }
+    /// draw a group header, the header can not be selected
+    pub fn draw_header(&mut self, cx: &mut Cx2d, text: &str) {
+        let _ = get_font_family(&self.font_family, cx, &mut self.draw_header.text_style.font);
+        cx.begin_turtle(
+            Walk {
+                width: Size::Fill,
+                height: Size::Fixed(self.header_height),
+                ..Default::default()
+            },
+            Layout {
+                align: Align { x: 0.0, y: 0.5 },
+                padding: self.header_padding,
+                ..Default::default()
+            },
+        );
+        self.draw_header
+            .draw_walk(cx, Walk::fit(), Align::default(), text);
+        cx.end_turtle();
+    }
+    /// start drawing the window, the slots are filled again by `draw_option`
+    pub fn clear_slots(&mut self) {
+        self.slots.clear();
     }
     pub fn handle_event_with(
         &mut self,
@@ -159,7 +285,9 @@ impl GSelectOptions {
         dispatch_action: &mut dyn FnMut(&mut Cx, GSelectOptionsEvent),
     ) {
         let mut actions = Vec::new();
-        for (item_id, node) in self.children.iter_mut() {
+        let slots = self.slots.len();
+        // the items not drawn in this frame are kept for reuse, but they get no event
+        for (item_id, node) in self.children.iter_mut().filter(|(id, _)| (id.0 as usize) < slots) {
             node.handle_event_with(cx, event, sweep_area, &mut |_, e| {
                 actions.push((*item_id, e))
             });
@@ -169,7 +297,7 @@ impl GSelectOptions {
             match action {
                 GSelectItemEvent::Clicked(param) => {
                     // if is item clicked, do options event change
-                    if param.selected {
+                    if param.selected && !self.multiple {
                         for (_index, (id, item)) in self.children.iter_mut().enumerate() {
                             if id.0 != node_id.0 {
                                 item.selected = false;
@@ -186,7 +314,8 @@ impl GSelectOptions {
                             selected: param.selected,
                             text: param.text,
                             value: param.value,
-                            selected_id: node_id.0 as usize,
+                            selected_id: self.slots[node_id.0 as usize],
+                            values: Vec::new(),
                             e: param.e,
                         }),
                     );
@@ -1,7 +1,16 @@
-#[derive(Debug, Clone, PartialEq)]
+use std::{rc::Rc, sync::mpsc::Sender};
+
+use makepad_widgets::SignalToUI;
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct SelectOption {
     pub text: String,
     pub value: String,
+    /// disabled option is drawn but can not be selected by click
+    pub disabled: bool,
+    /// options with the same group are drawn under a group header,
+    /// options of one group should be next to each other
+    pub group: Option<String>,
 }
 
 impl SelectOption {
@@ -9,8 +18,17 @@ impl SelectOption {
         Self {
             text: text.to_string(),
             value: value.to_string(),
+            ..Default::default()
         }
     }
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group.replace(group.to_string());
+        self
+    }
 }
 
 impl From<(&str, &str)> for SelectOption {
@@ -18,3 +36,34 @@ impl From<(&str, &str)> for SelectOption {
         Self::new(value.0, value.1)
     }
 }
+
+/// ## A handle to send the remote search result back to `GSelect`
+/// it can be moved to another thread, the result of an outdated query is dropped
+#[derive(Clone, Debug)]
+pub struct SelectLoaderSender {
+    pub(crate) query_id: u64,
+    pub(crate) sender: Sender<(u64, Vec<SelectOption>)>,
+}
+
+impl SelectLoaderSender {
+    pub fn query_id(&self) -> u64 {
+        self.query_id
+    }
+    /// send the options, the select will replace its options and stop loading
+    pub fn send(&self, options: Vec<SelectOption>) {
+        if self.sender.send((self.query_id, options)).is_ok() {
+            SignalToUI::set_ui_signal();
+        }
+    }
+}
+
+/// A remote loader, the args are the filter text and the sender to send the result back
+pub type SelectLoader = Rc<dyn Fn(&str, SelectLoaderSender)>;
+
+/// A row of the options list, the index is the index of the option
+/// (for `Header`, the first option of the group)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectRow {
+    Header(usize),
+    Option(usize),
+}
@@ -8,18 +8,110 @@ live_design!{
         opacity: 1.0
         image_scale: vec2(1.0, 1.0)
         image_pan: vec2(0.0, 0.0)
-                
+        placeholder_color: #E4E7EC
+        is_loading: 0.0
+        border_radius: 0.0
+        border_width: 0.0
+        border_color: #0000
+        object_position: vec2(0.5, 0.5)
+        crop: 0.0
+        grayscale: 0.0
+        brightness: 1.0
+        contrast: 1.0
+        saturation: 1.0
+        blur: 0.0
+        tint_color: #0000
+        hover_grayscale: 0.0
+        hover_brightness: 1.0
+        hover_contrast: 1.0
+        hover_saturation: 1.0
+        hover_blur: 0.0
+        hover_tint_color: #0000
+        hover: 0.0
+
+        // position in the texture, if crop the image covers the rect and `object_position` selects the visible part
+        fn get_uv(self) -> vec2 {
+            let uv = self.pos;
+            if self.crop > 0.5 && self.image_size.x > 0.0 && self.image_size.y > 0.0 {
+                let rect_aspect = self.rect_size.x / self.rect_size.y;
+                let image_aspect = self.image_size.x / self.image_size.y;
+                if image_aspect > rect_aspect {
+                    let visible = rect_aspect / image_aspect;
+                    uv.x = uv.x * visible + (1.0 - visible) * self.object_position.x;
+                } else {
+                    let visible = image_aspect / rect_aspect;
+                    uv.y = uv.y * visible + (1.0 - visible) * self.object_position.y;
+                }
+            }
+            return uv;
+        }
+
         fn get_color_scale_pan(self, scale: vec2, pan: vec2) -> vec4 {
-            return sample2d(self.image, self.pos * scale + pan).xyzw;
+            return sample2d(self.image, self.get_uv() * scale + pan).xyzw;
         }
                                 
         fn get_color(self) -> vec4 {
-            return self.get_color_scale_pan(self.image_scale, self.image_pan)
+            let blur = mix(self.blur, self.hover_blur, self.hover);
+            if blur <= 0.0 {
+                return self.get_color_scale_pan(self.image_scale, self.image_pan)
+            }
+            // gaussian blur of 5x5 samples, `blur` is the radius in px
+            let step = blur * 0.5 / self.rect_size * self.image_scale;
+            let color = vec4(0.0);
+            let total = 0.0;
+            for i in 0..5 {
+                for j in 0..5 {
+                    let offset = vec2(float(i) - 2.0, float(j) - 2.0);
+                    let weight = exp(-dot(offset, offset) * 0.5);
+                    color += self.get_color_scale_pan(self.image_scale, self.image_pan + offset * step) * weight;
+                    total += weight;
+                }
+            }
+            return color / total;
+        }
+
+        // brightness, contrast, saturation, grayscale then tint, the hover values are mixed by `hover`
+        fn adjust_color(self, color: vec4) -> vec4 {
+            let luma = vec3(0.2126, 0.7152, 0.0722);
+            let rgb = color.rgb * mix(self.brightness, self.hover_brightness, self.hover);
+            rgb = (rgb - 0.5) * mix(self.contrast, self.hover_contrast, self.hover) + 0.5;
+            rgb = mix(vec3(dot(rgb, luma)), rgb, mix(self.saturation, self.hover_saturation, self.hover));
+            rgb = mix(rgb, vec3(dot(rgb, luma)), mix(self.grayscale, self.hover_grayscale, self.hover));
+            let tint = mix(self.tint_color, self.hover_tint_color, self.hover);
+            rgb = mix(rgb, tint.rgb, tint.a);
+            return vec4(clamp(rgb, vec3(0.0), vec3(1.0)), color.a);
+        }
+
+        // draw the placeholder until the image is loaded
+        fn get_image_color(self) -> vec4 {
+            if self.is_loading > 0.5 {
+                return self.placeholder_color;
+            }
+            return self.adjust_color(self.get_color());
         }
         
         fn pixel(self) -> vec4 {
-            let color = self.get_color();
-            return Pal::premul(vec4(color.xyz, color.w * self.opacity))
+            let color = self.get_image_color();
+            // no shape, the edges stay sharp
+            if self.border_radius <= 0.0 && self.border_width <= 0.0 {
+                return color * self.opacity;
+            }
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            let half = self.border_width * 0.5;
+            // radius bigger than half of the size is a circle (or a capsule)
+            let radius = max(min(self.border_radius, min(self.rect_size.x, self.rect_size.y) * 0.5) - half, 0.0);
+            sdf.box(
+                half,
+                half,
+                self.rect_size.x - self.border_width,
+                self.rect_size.y - self.border_width,
+                radius
+            );
+            sdf.fill_keep(color);
+            if self.border_width > 0.0 {
+                sdf.stroke(self.border_color, self.border_width);
+            }
+            return sdf.result * self.opacity;
         }
     }
 }
@@ -31,4 +123,26 @@ pub struct DrawGImage {
     #[live] pub opacity: f32,
     #[live] pub image_scale: Vec2,
     #[live] pub image_pan: Vec2,
+    #[live] pub placeholder_color: Vec4,
+    #[live] pub is_loading: f32,
+    #[live] pub border_radius: f32,
+    #[live] pub border_width: f32,
+    #[live] pub border_color: Vec4,
+    #[live] pub object_position: Vec2,
+    #[live] pub crop: f32,
+    /// size of the texture, set before drawing
+    #[live] pub image_size: Vec2,
+    #[live] pub grayscale: f32,
+    #[live] pub brightness: f32,
+    #[live] pub contrast: f32,
+    #[live] pub saturation: f32,
+    #[live] pub blur: f32,
+    #[live] pub tint_color: Vec4,
+    #[live] pub hover_grayscale: f32,
+    #[live] pub hover_brightness: f32,
+    #[live] pub hover_contrast: f32,
+    #[live] pub hover_saturation: f32,
+    #[live] pub hover_blur: f32,
+    #[live] pub hover_tint_color: Vec4,
+    #[live] pub hover: f32,
 }
@@ -145,6 +145,8 @@ pub enum MenuItemMode {
     SubMenu(Vec<MenuItemMode>),
     /// menu item as a leaf node, `bool` is selected or not
     MenuItem(bool),
+    /// a `GDivider` between the items, it can not be selected
+    Separator,
 }
 
 impl MenuItemMode {
@@ -154,6 +156,9 @@ impl MenuItemMode {
     pub fn is_sub_menu(&self) -> bool {
         matches!(self, MenuItemMode::SubMenu(_))
     }
+    pub fn is_separator(&self) -> bool {
+        matches!(self, MenuItemMode::Separator)
+    }
     /// ## find node mode by levels
     /// `[1, 2]`: 0 => x |1 =>SubMenu(0 =>x, 1=> x, 2=> MenuItem) ..., means index 1 is sub menu, sub menu's index 2 is menu item
     pub fn find(items: &Vec<MenuItemMode>, levels: &Vec<usize>) -> Option<MenuItemMode> {
@@ -222,6 +227,7 @@ impl MenuItemMode {
                             return true;
                         }
                     }
+                    MenuItemMode::Separator => {}
                 }
             }
             flag
@@ -305,4 +311,18 @@ mod test_mode {
 
         dbg!(super::MenuItemMode::selected(&menu_items));
     }
+    #[test]
+    fn menu_separator() {
+        let menu_items = vec![
+            super::MenuItemMode::MenuItem(false),
+            super::MenuItemMode::Separator,
+            super::MenuItemMode::SubMenu(vec![
+                super::MenuItemMode::Separator,
+                super::MenuItemMode::MenuItem(true),
+            ]),
+        ];
+
+        assert_eq!(super::MenuItemMode::selected(&menu_items), Some(vec![2, 1]));
+        assert!(super::MenuItemMode::find(&menu_items, &vec![1]).is_some_and(|item| item.is_separator()));
+    }
 }
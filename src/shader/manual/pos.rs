@@ -58,6 +58,8 @@ pub enum Position {
     Bottom = shader_enum(10),
     BottomLeft = shader_enum(11),
     BottomRight = shader_enum(12),
+    /// the side with the most space, resolved by `Position::place`
+    Auto = shader_enum(13),
 }
 
 impl Default for Position {
@@ -72,13 +74,17 @@ impl Position {
             Position::Left | Position::LeftTop | Position::LeftBottom => Position::Left,
             Position::Right | Position::RightTop | Position::RightBottom => Position::Right,
             Position::Top | Position::TopLeft | Position::TopRight => Position::Top,
-            Position::Bottom | Position::BottomLeft | Position::BottomRight => Position::Bottom,
+            Position::Bottom | Position::BottomLeft | Position::BottomRight | Position::Auto => {
+                Position::Bottom
+            }
         }
     }
     /// return angle offset
     pub fn angle_offset(&self, size: DVec2) -> f32 {
         match self {
-            Position::Left | Position::Right | Position::Bottom | Position::Top => 0.0,
+            Position::Left | Position::Right | Position::Bottom | Position::Top | Position::Auto => {
+                0.0
+            }
             Position::LeftTop
             | Position::LeftBottom
             | Position::RightTop
@@ -90,3 +96,208 @@ impl Position {
         }
     }
 }
+
+impl Position {
+    /// ## shift of the popup from the anchor's top left corner
+    /// - `anchor`: size of the anchor (the widget which opens the popup)
+    /// - `container`: size of the popup
+    /// - `offset`: space between the anchor and the popup
+    pub fn shift(&self, anchor: DVec2, container: DVec2, offset: f64) -> DVec2 {
+        match self {
+            Position::Bottom | Position::Auto => {
+                dvec2(anchor.x / 2.0 - container.x / 2.0, anchor.y + offset)
+            }
+            Position::BottomLeft => dvec2(0.0, anchor.y + offset),
+            Position::BottomRight => dvec2(anchor.x - container.x, anchor.y + offset),
+            Position::Top => dvec2(anchor.x / 2.0 - container.x / 2.0, -offset - container.y),
+            Position::TopLeft => dvec2(0.0, -offset - container.y),
+            Position::TopRight => dvec2(anchor.x - container.x, -offset - container.y),
+            Position::Left => dvec2(-offset - container.x, anchor.y / 2.0 - container.y / 2.0),
+            Position::LeftTop => dvec2(-offset - container.x, 0.0),
+            Position::LeftBottom => dvec2(-offset - container.x, anchor.y - container.y),
+            Position::Right => dvec2(anchor.x + offset, anchor.y / 2.0 - container.y / 2.0),
+            Position::RightTop => dvec2(anchor.x + offset, 0.0),
+            Position::RightBottom => dvec2(anchor.x + offset, anchor.y - container.y),
+        }
+    }
+    /// the position on the opposite side of the anchor
+    pub fn flip(&self) -> Self {
+        match self {
+            Position::Left => Position::Right,
+            Position::LeftTop => Position::RightTop,
+            Position::LeftBottom => Position::RightBottom,
+            Position::Right => Position::Left,
+            Position::RightTop => Position::LeftTop,
+            Position::RightBottom => Position::LeftBottom,
+            Position::Top => Position::Bottom,
+            Position::TopLeft => Position::BottomLeft,
+            Position::TopRight => Position::BottomRight,
+            Position::Bottom => Position::Top,
+            Position::BottomLeft => Position::TopLeft,
+            Position::BottomRight => Position::TopRight,
+            Position::Auto => Position::Auto,
+        }
+    }
+    pub fn is_vertical(&self) -> bool {
+        matches!(self.to_drawer(), Position::Top | Position::Bottom)
+    }
+    /// space between the anchor and the viewport edge on this side
+    pub fn space(&self, anchor: Rect, offset: f64, viewport: Rect) -> f64 {
+        match self.to_drawer() {
+            Position::Left => anchor.pos.x - viewport.pos.x - offset,
+            Position::Right => {
+                viewport.pos.x + viewport.size.x - (anchor.pos.x + anchor.size.x) - offset
+            }
+            Position::Top => anchor.pos.y - viewport.pos.y - offset,
+            _ => viewport.pos.y + viewport.size.y - (anchor.pos.y + anchor.size.y) - offset,
+        }
+    }
+    /// the side (`Bottom`, `Top`, `Right` or `Left`) with the most space, `Bottom` wins a tie
+    pub fn auto(anchor: Rect, offset: f64, viewport: Rect) -> Self {
+        [Position::Top, Position::Right, Position::Left]
+            .into_iter()
+            .fold(Position::Bottom, |best, side| {
+                if side.space(anchor, offset, viewport) > best.space(anchor, offset, viewport) {
+                    side
+                } else {
+                    best
+                }
+            })
+    }
+    /// ## place the popup in the viewport
+    /// if the popup overflows the viewport on this side and the opposite side has more space,
+    /// it is flipped, then it slides along the edge to stay in the viewport.
+    /// `Auto` starts from the side with the most space.
+    /// back the used position and the shift from the anchor's top left corner
+    pub fn place(&self, anchor: Rect, container: DVec2, offset: f64, viewport: Rect) -> (Self, DVec2) {
+        let start = match self {
+            Position::Auto => Position::auto(anchor, offset, viewport),
+            _ => *self,
+        };
+        let main = if start.is_vertical() { container.y } else { container.x };
+        let position = if start.space(anchor, offset, viewport) < main
+            && start.flip().space(anchor, offset, viewport) > start.space(anchor, offset, viewport)
+        {
+            start.flip()
+        } else {
+            start
+        };
+        let mut shift = position.shift(anchor.size, container, offset);
+        // slide along the edge, the start edge wins if the popup is larger than the viewport
+        let clamp = |pos: f64, size: f64, start: f64, len: f64| -> f64 {
+            pos.min(start + len - size).max(start)
+        };
+        if position.is_vertical() {
+            let x = anchor.pos.x + shift.x;
+            shift.x += clamp(x, container.x, viewport.pos.x, viewport.size.x) - x;
+        } else {
+            let y = anchor.pos.y + shift.y;
+            shift.y += clamp(y, container.y, viewport.pos.y, viewport.size.y) - y;
+        }
+        (position, shift)
+    }
+    /// ## the arrow of a placed popup
+    /// back the position to draw and the `angle_offset` which makes the arrow point at the anchor center,
+    /// the arrow is measured from the start edge of the popup (`*Left` or `*Top` variants).
+    pub fn arrow(&self, anchor: DVec2, container: DVec2, shift: DVec2) -> (Self, f32) {
+        const MARGIN: f64 = 10.0;
+        let clamp = |v: f64, len: f64| v.min(len - MARGIN).max(MARGIN.min(len / 2.0));
+        match self.to_drawer() {
+            Position::Top => (Position::TopLeft, clamp(anchor.x / 2.0 - shift.x, container.x) as f32),
+            Position::Bottom => (
+                Position::BottomLeft,
+                clamp(anchor.x / 2.0 - shift.x, container.x) as f32,
+            ),
+            Position::Left => (Position::LeftTop, clamp(anchor.y / 2.0 - shift.y, container.y) as f32),
+            _ => (Position::RightTop, clamp(anchor.y / 2.0 - shift.y, container.y) as f32),
+        }
+    }
+}
+
+/// is `pos` in the convex hull of `apex` and `rect` (the safe triangle from the pointer to the popup)
+fn in_safe_area(apex: DVec2, rect: Rect, pos: DVec2) -> bool {
+    let corners = [
+        rect.pos,
+        dvec2(rect.pos.x + rect.size.x, rect.pos.y),
+        rect.pos + rect.size,
+        dvec2(rect.pos.x, rect.pos.y + rect.size.y),
+    ];
+    let cross = |o: DVec2, a: DVec2, b: DVec2| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+    (0..4).any(|i| {
+        let (b, c) = (corners[i], corners[(i + 1) % 4]);
+        let d = [cross(apex, b, pos), cross(b, c, pos), cross(c, apex, pos)];
+        !(d.iter().any(|v| *v < 0.0) && d.iter().any(|v| *v > 0.0))
+    })
+}
+
+#[cfg(test)]
+mod test_pos {
+    use super::{in_safe_area, Position};
+    use makepad_widgets::{dvec2, Rect};
+
+    fn viewport() -> Rect {
+        Rect {
+            pos: dvec2(0.0, 0.0),
+            size: dvec2(400.0, 300.0),
+        }
+    }
+
+    #[test]
+    fn place_flip() {
+        let anchor = Rect {
+            pos: dvec2(10.0, 250.0),
+            size: dvec2(100.0, 30.0),
+        };
+        let (position, shift) =
+            Position::Bottom.place(anchor, dvec2(100.0, 120.0), 6.0, viewport());
+        assert!(matches!(position, Position::Top));
+        assert_eq!(shift, dvec2(0.0, -126.0));
+    }
+    #[test]
+    fn place_clamp() {
+        let anchor = Rect {
+            pos: dvec2(350.0, 10.0),
+            size: dvec2(40.0, 30.0),
+        };
+        let (position, shift) =
+            Position::Bottom.place(anchor, dvec2(200.0, 100.0), 6.0, viewport());
+        assert!(matches!(position, Position::Bottom));
+        // popup right edge is on the viewport right edge
+        assert_eq!(anchor.pos.x + shift.x, 200.0);
+        assert_eq!(shift.y, 36.0);
+    }
+    #[test]
+    fn place_auto() {
+        let anchor = Rect {
+            pos: dvec2(360.0, 130.0),
+            size: dvec2(30.0, 30.0),
+        };
+        // most space is on the left
+        let (position, shift) = Position::Auto.place(anchor, dvec2(80.0, 40.0), 6.0, viewport());
+        assert!(matches!(position, Position::Left));
+        let (arrow, offset) = position.arrow(anchor.size, dvec2(80.0, 40.0), shift);
+        assert!(matches!(arrow, Position::LeftTop));
+        assert_eq!(offset, 20.0);
+    }
+    #[test]
+    fn safe_area() {
+        let popup = Rect {
+            pos: dvec2(100.0, 0.0),
+            size: dvec2(50.0, 100.0),
+        };
+        let apex = dvec2(0.0, 50.0);
+        // in the triangle to the popup, on its edge, and in the popup
+        assert!(in_safe_area(apex, popup, dvec2(50.0, 50.0)));
+        assert!(in_safe_area(apex, popup, dvec2(50.0, 25.0)));
+        assert!(in_safe_area(apex, popup, dvec2(120.0, 10.0)));
+        // out of the triangle, behind the apex and beyond the popup
+        assert!(!in_safe_area(apex, popup, dvec2(50.0, 10.0)));
+        assert!(!in_safe_area(apex, popup, dvec2(-10.0, 50.0)));
+        assert!(!in_safe_area(apex, popup, dvec2(200.0, 50.0)));
+        // the apex on the popup edge, the area is the popup itself
+        let apex = dvec2(100.0, 50.0);
+        assert!(in_safe_area(apex, popup, apex));
+        assert!(in_safe_area(apex, popup, dvec2(130.0, 90.0)));
+        assert!(!in_safe_area(apex, popup, dvec2(90.0, 50.0)));
+    }
+}
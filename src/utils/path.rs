@@ -87,12 +87,14 @@ impl HeapLiveIdPathExp for HeapLiveIdPath {
     }
 }
 
+/// The name of the directory in the system temp directory which holds the files of the app.
+const TEMP_DIR_NAME: &str = "robrix_temp";
+
 /// Creates and returns the path to a temp directory for storage.
 ///
 /// This is very efficient to call multiple times because the result is cached
 /// after the first call creates the temp directory.
 pub fn tmp_path() -> &'static PathBuf {
-    const TEMP_DIR_NAME: &str = "robrix_temp";
     static TEMP_DIR_PATH: OnceLock<PathBuf> = OnceLock::new();
 
     TEMP_DIR_PATH.get_or_init(|| {
@@ -101,4 +103,19 @@ pub fn tmp_path() -> &'static PathBuf {
         std::fs::create_dir_all(&path).expect("Failed to create temp dir: {path}");
         path
     })
-}
\ No newline at end of file
+}
+/// Creates and returns the directory in the temp directory which stores the downloaded images,
+/// `None` if the directory can not be created.
+pub fn image_cache_path() -> Option<&'static PathBuf> {
+    const IMAGE_CACHE_DIR_NAME: &str = "image_cache";
+    static IMAGE_CACHE_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    IMAGE_CACHE_PATH
+        .get_or_init(|| {
+            let path = std::env::temp_dir()
+                .join(TEMP_DIR_NAME)
+                .join(IMAGE_CACHE_DIR_NAME);
+            std::fs::create_dir_all(&path).ok().map(|_| path)
+        })
+        .as_ref()
+}